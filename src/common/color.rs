@@ -69,6 +69,12 @@ impl Color {
            f(self.b(), rhs.b())])
   }
 
+  pub fn map<F>(&self, f: F) -> Color
+    where F: Fn(f32) -> f32
+  {
+    Color([f(self.r()), f(self.g()), f(self.b())])
+  }
+
   pub fn clamp(&self, min: f32, max: f32) -> Color {
     Color([self.r().max(min).min(max),
            self.g().max(min).min(max),
@@ -83,11 +89,35 @@ impl Color {
     self.mix_with(brightness, |a, b| a * b)
   }
 
+  // 8-bit sRGB encoded values, like the ones from color pickers, decoded
+  // to the linear working space
   pub fn from_rgb(hex: [u32; 3]) -> Self {
+    let [r, g, b] = hex;
+    Self::from_srgb(Color([r as f32, g as f32, b as f32]) * (1.0 / 255.0))
+  }
+
+  // sRGB encoded hex code like 0xff8800
+  #[allow(unused)]
+  pub fn from_hex(hex: u32) -> Self {
+    Self::from_rgb([(hex >> 16) & 0xff, (hex >> 8) & 0xff, hex & 0xff])
+  }
+
+  // 8-bit linear values, without decoding
+  #[allow(unused)]
+  pub fn from_linear_rgb(hex: [u32; 3]) -> Self {
     let [r, g, b] = hex;
     Self([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0])
   }
 
+  pub fn from_srgb(c: Color) -> Self {
+    c.map(srgb_to_linear)
+  }
+
+  #[allow(unused)]
+  pub fn to_srgb(&self) -> Color {
+    self.map(linear_to_srgb)
+  }
+
   pub fn random() -> Self {
     Color::from_rgb(RandomColor::new().to_rgb_array())
   }
@@ -112,15 +142,61 @@ impl PartialEq for Color {
   }
 }
 
+// Transfer function of the values stored in images and color constants.
+// Shading happens in linear RGB, sRGB values must be decoded first.
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorSpace {
+  Linear,
+  Srgb,
+}
+
+impl ColorSpace {
+  // encoded values to linear
+  pub fn decode(self, c: Color) -> Color {
+    match self {
+      ColorSpace::Linear => c,
+      ColorSpace::Srgb => Color::from_srgb(c),
+    }
+  }
+
+  // linear values to encoded
+  pub fn encode(self, c: Color) -> Color {
+    match self {
+      ColorSpace::Linear => c,
+      ColorSpace::Srgb => c.map(linear_to_srgb),
+    }
+  }
+}
+
+// sRGB electro-optical transfer function
+pub fn srgb_to_linear(x: f32) -> f32 {
+  if x <= 0.040_45 {
+    x / 12.92
+  } else {
+    ((x + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+// sRGB opto-electronic transfer function
+pub fn linear_to_srgb(x: f32) -> f32 {
+  if x <= 0.003_130_8 {
+    12.92 * x
+  } else {
+    1.055 * x.powf(1.0 / 2.4) - 0.055
+  }
+}
+
+// quantizes the already encoded color, out of range values are clamped
 impl Into<[u8; 3]> for Color {
   fn into(self) -> [u8; 3] {
     let normalize = |k: f32| {
       if k < 0.0 {
         0
       } else if k > 1.0 {
-        1
+        255
       } else {
-        (k * 255.0) as u8
+        (k * 255.0 + 0.5) as u8
       }
     };
     [normalize(self.r()),
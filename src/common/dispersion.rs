@@ -0,0 +1,54 @@
+// Wavelength dependent index of refraction
+
+// wavelengths (in nm) representing the RGB channels when tracing dispersive
+// materials per channel
+pub const RGB_WAVELENGTHS: [f32; 3] = [630.0, 532.0, 465.0];
+
+// Fraunhofer D line, the wavelength at which the IOR is usually quoted
+pub const D_LINE: f32 = 589.3;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub enum Dispersion {
+  // n(l) = a + b / l^2, l in micrometers
+  Cauchy { a: f32, b: f32 },
+  // n(l)^2 = 1 + sum(b_i * l^2 / (l^2 - c_i)), l in micrometers
+  Sellmeier { b: [f32; 3], c: [f32; 3] },
+}
+
+#[allow(unused)]
+impl Dispersion {
+  // See: https://refractiveindex.info
+  pub const BK7: Dispersion = Dispersion::Sellmeier {
+    b: [1.039_612_1, 0.231_792_34, 1.010_469_5],
+    c: [0.006_000_699, 0.020_017_914, 103.560_65],
+  };
+  pub const DENSE_FLINT: Dispersion = Dispersion::Sellmeier {
+    b: [1.737_597, 0.313_747_35, 1.898_781],
+    c: [0.013_188_707, 0.062_306_814, 155.236_29],
+  };
+  pub const DIAMOND: Dispersion = Dispersion::Sellmeier {
+    b: [4.3356, 0.3306, 0.0],
+    c: [0.011_236, 0.030_625, 0.0],
+  };
+  pub const WATER: Dispersion = Dispersion::Cauchy {
+    a: 1.3199,
+    b: 0.006_878,
+  };
+
+  // index of refraction at the given wavelength in nm
+  pub fn ior(&self, wavelength: f32) -> f32 {
+    let l = wavelength / 1000.0;
+    let l2 = l * l;
+    match self {
+      Dispersion::Cauchy { a, b } => a + b / l2,
+      Dispersion::Sellmeier { b, c } => {
+        let mut n2 = 1.0;
+        for k in 0..3 {
+          n2 += b[k] * l2 / (l2 - c[k]);
+        }
+        n2.sqrt()
+      }
+    }
+  }
+}
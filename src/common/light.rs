@@ -1,8 +1,84 @@
-use super::{Color, V3};
+use super::{Color, Spectrum, V3};
+
+// How the light received from a point light decreases with distance.
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub enum Falloff {
+  // no attenuation: a light at 1m and at 100m illuminate identically
+  Constant,
+  // attenuation of 1/d
+  Linear,
+  // attenuation of 1/d^2, physically correct for point lights
+  InverseSquare,
+  // custom curve, maps distance to an attenuation factor
+  Custom(fn(f32) -> f32),
+}
 
 #[derive(Debug, Clone)]
 pub struct PointLight {
   pub pos: V3,
   pub color: Color,
-  pub brightness: f32, // 0: turned off
+  // Radiant intensity in W/sr (0: turned off).
+  //
+  // With `Falloff::InverseSquare` a surface facing the light at distance d
+  // receives an irradiance of brightness / d^2 (W/m^2, with scene units
+  // taken as meters). With `Falloff::Constant` the brightness is the
+  // irradiance received at any distance.
+  pub brightness: f32,
+  pub falloff: Falloff,
+  // emission spectrum used in spectral mode, color is its RGB projection
+  pub spectrum: Option<Spectrum>,
+}
+
+impl Falloff {
+  pub fn attenuation(&self, dist: f32) -> f32 {
+    // avoid the singularity when a surface touches the light
+    let d = dist.max(1e-4);
+    match self {
+      Falloff::Constant => 1.0,
+      Falloff::Linear => 1.0 / d,
+      Falloff::InverseSquare => 1.0 / (d * d),
+      Falloff::Custom(f) => f(dist),
+    }
+  }
+}
+
+impl Default for Falloff {
+  fn default() -> Falloff {
+    Falloff::Constant
+  }
+}
+
+impl PointLight {
+  // irradiance received from the light at the given point, ignoring the
+  // angle of incidence and shadowing. Gives the irradiance at the single
+  // wavelength (as a gray color) if given one and the light has a spectrum.
+  pub fn irradiance_at(&self, p: V3, wavelength: Option<f32>) -> Color {
+    let dist = (self.pos - p).magn();
+    let color = match (&self.spectrum, wavelength) {
+      (Some(spectrum), Some(l)) => Color::from_intensity(spectrum.at(l)),
+      _ => self.color,
+    };
+    color * self.brightness * self.falloff.attenuation(dist)
+  }
+}
+
+// An object registered as a light source. The object (referred by its index
+// in the scene) is sampled over its surface for soft shadows.
+#[derive(Debug, Clone)]
+pub struct EmissiveLight {
+  pub obj: usize,
+  // emitted radiance in W/(sr m^2)
+  pub radiance: Color,
+  // number of shadow rays per shading point
+  pub samples: usize,
+}
+
+// A point on a light source as seen from a shading point.
+#[derive(Debug, Clone)]
+pub struct LightSample {
+  pub pos: V3,
+  // irradiance arriving at the shading point, before the cosine term
+  // of the receiving surface and shadowing are applied
+  pub irradiance: Color,
 }
@@ -6,7 +6,6 @@ pub fn dist2(a: V3, b: V3) -> f32 {
   let d = b - a;
   d.dot(d)
 }
-#[allow(unused)]
 pub fn dist(a: V3, b: V3) -> f32 {
   dist2(a, b).sqrt()
 }
@@ -38,3 +37,19 @@ pub fn randn(mean: f32, std_dev: f32) -> f32 {
 pub fn randn0() -> f32 {
   randn(0.0, 1.0)
 }
+
+// uniformly distributed in [0, 1)
+pub fn rand01() -> f32 {
+  use rand::{thread_rng, Rng};
+  thread_rng().gen()
+}
+
+// uniformly distributed direction on the unit sphere
+pub fn rand_unit_v3() -> V3 {
+  loop {
+    let v = randn_v3(0.0, 1.0);
+    if !v.is_zero() {
+      return v.norm();
+    }
+  }
+}
@@ -4,21 +4,27 @@ use std::ops::{Add, Neg};
 
 mod bound;
 mod color;
+mod dispersion;
 mod light;
 mod math;
+mod noise;
 mod ray;
 mod shape;
+mod spectrum;
 mod transformation;
 mod trig;
 mod v2;
 mod v3;
 
 pub use bound::{Bound, BoundingBox, BoundingSphere};
-pub use color::Color;
-pub use light::PointLight;
+pub use color::{Color, ColorSpace};
+pub use dispersion::{Dispersion, D_LINE, RGB_WAVELENGTHS};
+pub use light::{EmissiveLight, Falloff, LightSample, PointLight};
 pub use math::*;
+pub use noise::fbm;
 pub use ray::{Hit, Ray};
 pub use shape::{Line, Plane};
+pub use spectrum::{sample_wavelength, spectral_to_rgb, Spectrum};
 pub use transformation::{TransMat, M33, M4};
 pub use trig::{Trig, TrigGen, TrigN};
 pub use v2::V2;
@@ -0,0 +1,45 @@
+use super::V3;
+
+// pseudo random value in [0, 1] at an integer lattice point
+fn hash(x: i32, y: i32, z: i32) -> f32 {
+  let mut h = (x as u32).wrapping_mul(0x8da6_b343)
+              ^ (y as u32).wrapping_mul(0xd816_3841)
+              ^ (z as u32).wrapping_mul(0xcb1a_b31f);
+  h ^= h >> 13;
+  h = h.wrapping_mul(0x5bd1_e995);
+  h ^= h >> 15;
+  (h & 0xff_ffff) as f32 / 0xff_ffff as f32
+}
+
+// smoothly interpolated lattice noise in [0, 1]
+pub fn value_noise(p: V3) -> f32 {
+  let (fx, fy, fz) = (p.x().floor(), p.y().floor(), p.z().floor());
+  let (x, y, z) = (fx as i32, fy as i32, fz as i32);
+  let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+  let (tx, ty, tz) =
+    (smooth(p.x() - fx), smooth(p.y() - fy), smooth(p.z() - fz));
+  let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+  let mut layers = [0.0; 2];
+  for dz in 0..2 {
+    let c00 = lerp(hash(x, y, z + dz), hash(x + 1, y, z + dz), tx);
+    let c10 = lerp(hash(x, y + 1, z + dz), hash(x + 1, y + 1, z + dz), tx);
+    layers[dz as usize] = lerp(c00, c10, ty);
+  }
+  lerp(layers[0], layers[1], tz)
+}
+
+// fractal sum of value noise octaves, in [0, 1]
+pub fn fbm(p: V3, octaves: usize) -> f32 {
+  let mut sum = 0.0;
+  let mut total = 0.0;
+  let mut amplitude = 1.0;
+  let mut p = p;
+  for _ in 0..octaves.max(1) {
+    sum += value_noise(p) * amplitude;
+    total += amplitude;
+    amplitude *= 0.5;
+    p = p * 2.0;
+  }
+  sum / total
+}
@@ -5,6 +5,9 @@ pub struct Ray {
   pub orig: V3,
   // direction, must be normalized
   pub dir: V3,
+  // wavelength in nm carried by the ray, None for a ray carrying all
+  // wavelengths (RGB)
+  pub wavelength: Option<f32>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -19,6 +22,14 @@ impl Ray {
     Self {
       orig,
       dir: dir.norm(),
+      wavelength: None,
+    }
+  }
+
+  pub fn with_wavelength(self, wavelength: f32) -> Ray {
+    Ray {
+      wavelength: Some(wavelength),
+      ..self
     }
   }
 
@@ -27,10 +38,19 @@ impl Ray {
     Self {
       orig: hit.pos,
       dir: self.dir - proj_n_d * 2.0,
+      wavelength: self.wavelength,
     }
     // Ray::new(hit.pos, self.dir - proj_n_d * 2.0)
   }
 
+  // a new ray continuing the path of this one
+  pub fn scattered(&self, orig: V3, dir: V3) -> Ray {
+    Ray {
+      wavelength: self.wavelength,
+      ..Ray::new(orig, dir)
+    }
+  }
+
   pub fn refract(&self, hit: &Hit, ior: f32) -> Ray {
     let i = self.dir;
     let mut n = hit.norm;
@@ -52,7 +72,7 @@ impl Ray {
       i * eta + n * (eta * cosi - k.sqrt())
     };
 
-    Ray::new(hit.pos, dir)
+    self.scattered(hit.pos, dir)
   }
 
   #[allow(unused)]
@@ -64,7 +84,7 @@ impl Ray {
     let dx = randn(0.0, std_dev);
     let dy = randn(0.0, std_dev);
     let dz = randn(0.0, std_dev);
-    Ray::new(self.orig, self.dir + V3([dx, dy, dz]))
+    self.scattered(self.orig, self.dir + V3([dx, dy, dz]))
   }
 
   // amount = 0: no drift
@@ -93,7 +113,7 @@ impl Add<V3> for Ray {
   fn add(self, rhs: V3) -> Ray {
     Ray {
       orig: self.orig + rhs,
-      dir: self.dir,
+      ..self
     }
   }
 }
@@ -102,8 +122,8 @@ impl Neg for Ray {
   type Output = Ray;
   fn neg(self) -> Ray {
     Ray {
-      orig: self.orig,
       dir: -self.dir,
+      ..self
     }
   }
 }
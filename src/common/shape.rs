@@ -160,6 +160,13 @@ impl Trig {
     self.ab().cross(self.ac()).magn() / 2.0
   }
 
+  // uniformly distributed point on the triangle
+  pub fn sample_point(&self) -> V3 {
+    let su = rand01().sqrt();
+    let v = rand01();
+    self.at_uv(V2([su * (1.0 - v), su * v]))
+  }
+
   pub fn at_uv(&self, uv: V2) -> V3 {
     self.a() * uv.w() + self.b() * uv.u() + self.c() * uv.v()
   }
@@ -0,0 +1,129 @@
+// Spectral data and conversions between spectra and RGB
+use super::Color;
+
+// range of visible wavelengths sampled in spectral mode, in nm
+pub const WAVELENGTH_MIN: f32 = 380.0;
+pub const WAVELENGTH_MAX: f32 = 730.0;
+
+// average of `cie_rgb` over the visible range, normalizes an equal energy
+// spectrum to white
+const CIE_RGB_NORM: [f32; 3] = [0.366_227_6, 0.289_713_5, 0.276_895_4];
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub enum Spectrum {
+  // upsampled from RGB, see `Color::spectral_value`
+  Rgb(Color),
+  // values at regular intervals of wavelengths, linearly interpolated
+  Sampled { start: f32, step: f32, values: Vec<f32> },
+  // black body radiation at the given temperature (in K), normalized to
+  // peak at 1.0
+  Blackbody(f32),
+}
+
+#[allow(unused)]
+impl Spectrum {
+  // value at the wavelength in nm
+  pub fn at(&self, wavelength: f32) -> f32 {
+    match self {
+      Spectrum::Rgb(c) => c.spectral_value(wavelength),
+      Spectrum::Sampled {
+        start,
+        step,
+        values,
+      } => {
+        let x = ((wavelength - start) / step).max(0.0);
+        let i = x as usize;
+        if i + 1 >= values.len() {
+          return values.last().cloned().unwrap_or(0.0);
+        }
+        let t = x - i as f32;
+        values[i] * (1.0 - t) + values[i + 1] * t
+      }
+      Spectrum::Blackbody(t) => {
+        // Wien's displacement law gives the peak wavelength
+        let peak = 2.897_772e6 / t;
+        planck(wavelength, *t) / planck(peak, *t)
+      }
+    }
+  }
+
+  // projects the spectrum to linear RGB
+  pub fn to_rgb(&self) -> Color {
+    if let Spectrum::Rgb(c) = self {
+      return *c;
+    }
+
+    let n = 100;
+    let mut sum = Color::Black;
+    for k in 0..n {
+      let l = sample_wavelength((k as f32 + 0.5) / n as f32);
+      sum = sum + spectral_to_rgb(l, self.at(l));
+    }
+    sum * (1.0 / n as f32)
+  }
+}
+
+// spectral radiance of a black body, wavelength in nm and temperature in K
+fn planck(wavelength: f32, t: f32) -> f32 {
+  let l = wavelength as f64 * 1e-9;
+  let (h, c, k) = (6.626_07e-34, 2.997_925e8, 1.380_649e-23);
+  let e = (h * c / (l * k * t as f64)).exp() - 1.0;
+  (2.0 * h * c * c / l.powi(5) / e) as f32
+}
+
+// CIE 1931 color matching functions, multi-lobe gaussian fit
+// See: http://jcgt.org/published/0002/02/01/
+pub fn cie_xyz(wavelength: f32) -> [f32; 3] {
+  let l = wavelength;
+  let g = |mu: f32, s1: f32, s2: f32| {
+    let s = if l < mu { s1 } else { s2 };
+    let t = (l - mu) / s;
+    (-0.5 * t * t).exp()
+  };
+
+  let x = 1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7)
+          - 0.065 * g(501.1, 20.4, 26.2);
+  let y = 0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1);
+  let z = 1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8);
+  [x, y, z]
+}
+
+// CIE XYZ to linear sRGB (D65)
+pub fn xyz_to_rgb([x, y, z]: [f32; 3]) -> Color {
+  Color([3.2406 * x - 1.5372 * y - 0.4986 * z,
+         -0.9689 * x + 1.8758 * y + 0.0415 * z,
+         0.0557 * x - 0.2040 * y + 1.0570 * z])
+}
+
+// linear RGB response of a single wavelength, normalized so averaging it
+// over uniformly sampled wavelengths of a constant spectrum gives white
+pub fn cie_rgb(wavelength: f32) -> Color {
+  let [r, g, b] = CIE_RGB_NORM;
+  xyz_to_rgb(cie_xyz(wavelength)) * Color([1.0 / r, 1.0 / g, 1.0 / b])
+}
+
+// contribution of a spectral sample to the RGB film
+pub fn spectral_to_rgb(wavelength: f32, value: f32) -> Color {
+  cie_rgb(wavelength) * value
+}
+
+// uniformly samples a visible wavelength, u in [0, 1)
+pub fn sample_wavelength(u: f32) -> f32 {
+  WAVELENGTH_MIN + (WAVELENGTH_MAX - WAVELENGTH_MIN) * u
+}
+
+impl Color {
+  // RGB to spectrum upsampling. Blends smooth blue, green and red basis
+  // functions which sum to one, so gray levels map to constant spectra.
+  pub fn spectral_value(&self, wavelength: f32) -> f32 {
+    let smoothstep = |a: f32, b: f32| {
+      let t = ((wavelength - a) / (b - a)).max(0.0).min(1.0);
+      t * t * (3.0 - 2.0 * t)
+    };
+    let blue = 1.0 - smoothstep(470.0, 505.0);
+    let red = smoothstep(555.0, 620.0);
+    let green = 1.0 - blue - red;
+    self.r() * red + self.g() * green + self.b() * blue
+  }
+}
@@ -172,10 +172,18 @@ impl M4 {
     Self(m)
   }
 
+  // determinant of the linear part (excluding translation)
+  pub fn det3(self) -> f32 {
+    let m = self.0;
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+    - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+    + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+  }
+
   pub fn transform_ray(self, r: &Ray) -> Ray {
     let new_orig = self.transform_point(r.orig);
     let new_dir = self.transform_vector(r.dir).norm();
-    Ray::new(new_orig, new_dir)
+    r.scattered(new_orig, new_dir)
   }
 
   pub fn transform_hit(self, trans_norm: Self, h: &Hit) -> Hit {
@@ -0,0 +1,61 @@
+use crate::common::*;
+
+use crate::object::{ChessBoard, Object, Sphere};
+use crate::scene::{Scene, SceneBuilder};
+use crate::shader::{self, DynValue, Incidence, ThinFilm};
+
+pub fn scene() -> Scene {
+    let mut scene = SceneBuilder::default()
+        .vp_plane(Plane::new(
+            V3([0.0, 0.0, -2.0]), // r0
+            V3([0.0, 0.0, -1.0]), // n
+        ))
+        .vp_width(2.0)
+        .vp_height(2.0)
+        .camera(V3::zero())
+        .projection(Projection::Perspective)
+        .ambient(Color::White * 0.3)
+        .background_light(Color([0.3; 3]))
+        .max_depth(10)
+        .build()
+        .unwrap();
+
+    scene.add_white_light(V3([-5.0, 10.0, 0.0]), 1.0);
+
+    // the film drains down, thinner at the top of the bubble
+    for (k, &c) in [V3([-1.2, 0.2, -5.0]), V3([0.8, -0.3, -6.0])]
+        .iter()
+        .enumerate()
+    {
+        let thickness = move |_s: &Scene, i: &Incidence<'_, '_, '_>| {
+            let h = i.hit.pos.y() - c.y();
+            200.0 + 300.0 * (1.0 - h) + 150.0 * fbm(i.hit.pos * 3.0, 3)
+        };
+        let r = 0.9 - k as f32 * 0.2;
+        scene.add_object(
+            Sphere { c, r }
+                .shaded(shader::soap_bubble(DynValue::from_fn(thickness))),
+        );
+    }
+
+    // anti-reflective coating on glass reflects a faint purple
+    let coated = ThinFilm::new(120.0.into(), 1.38.into(), 1.5.into());
+    scene.add_object(
+        Sphere {
+            c: V3([1.8, 0.8, -8.0]),
+            r: 0.8,
+        }
+        .shaded(coated.into()),
+    );
+
+    scene.add_object(ChessBoard {
+        plane: Plane::new(V3([0.0, -1.6, 0.0]), V3([0.0, 1.0, 0.0])),
+        material: (
+            shader::simple_solid(Color([0.2; 3])),
+            shader::simple_solid(Color([0.8; 3])),
+        ),
+        ..ChessBoard::default()
+    });
+
+    scene
+}
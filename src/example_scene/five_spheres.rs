@@ -56,10 +56,7 @@ pub fn scene() -> Scene {
             c: V3([0.0, 0.0, 0.0]),
             r: 1.0,
         }
-        .shaded(
-            shader::Rough::new(shader::simple_glass(Color::Red, 0.95), 0.005)
-                .into(),
-        )
+        .shaded(shader::simple_frosted_glass(Color::Red, 0.95, 0.1))
         .transformed()
         .scaled(V3([1.5, 1.5, 1.5]))
         .translated(V3([0.04 * 1.5, -0.52 * 1.5, -4.0 * 1.5])),
@@ -0,0 +1,75 @@
+use crate::common::*;
+
+use crate::medium::Medium;
+use crate::object::{ChessBoard, Object, Rectangle, Sphere};
+use crate::scene::{Scene, SceneBuilder};
+use crate::shader::{self, Transparency, Volume};
+
+pub fn scene() -> Scene {
+    let fog = Medium::new(Color([0.005; 3]), Color([0.03; 3]))
+        .anisotropy(0.6)
+        .max_distance(30.0);
+
+    let mut scene = SceneBuilder::default()
+        .vp_plane(Plane::new(
+            V3([0.0, 0.0, -2.0]), // r0
+            V3([0.0, 0.0, -1.0]), // n
+        ))
+        .vp_width(2.0)
+        .vp_height(2.0)
+        .camera(V3::zero())
+        .projection(Projection::Perspective)
+        .ambient(Color([0.05; 3]))
+        .background_light(Color([0.05; 3]))
+        .fog(Some(fog))
+        .max_depth(8)
+        .build()
+        .unwrap();
+
+    // light shining through a gap between two walls makes god rays
+    scene.add_white_light(V3([0.0, 6.0, -14.0]), 1.0);
+    scene.add_object(
+        Rectangle::new(
+            V3([-0.3, 4.0, -8.0]),
+            V3([-0.3, 4.0, -12.0]),
+            V3([-8.0, 4.0, -12.0]),
+        )
+        .double_sided(true)
+        .shaded(shader::simple_solid(Color([0.3; 3]))),
+    );
+    scene.add_object(
+        Rectangle::new(
+            V3([8.0, 4.0, -8.0]),
+            V3([8.0, 4.0, -12.0]),
+            V3([0.3, 4.0, -12.0]),
+        )
+        .double_sided(true)
+        .shaded(shader::simple_solid(Color([0.3; 3]))),
+    );
+
+    // a puff of smoke and a smoky glass ball
+    let smoke = Medium::new(Color([0.2; 3]), Color([1.5; 3])).noise(2.0, 4);
+    scene.add_object(
+        Sphere {
+            c: V3([-1.8, -0.4, -7.0]),
+            r: 1.2,
+        }
+        .shaded(Volume::new(smoke).into()),
+    );
+
+    let haze = Medium::new(Color([0.1, 0.2, 0.3]), Color([0.6; 3]));
+    scene.add_object(
+        Sphere {
+            c: V3([1.8, -0.4, -7.0]),
+            r: 1.2,
+        }
+        .shaded(Transparency::new(0.8, 1.5.into()).with_medium(haze).into()),
+    );
+
+    scene.add_object(ChessBoard {
+        plane: Plane::new(V3([0.0, -1.6, 0.0]), V3([0.0, 1.0, 0.0])),
+        ..ChessBoard::default()
+    });
+
+    scene
+}
@@ -1,6 +1,10 @@
 #![allow(unused)]
 
 pub mod basic;
+pub mod bubbles;
 pub mod five_spheres;
+pub mod fog;
+pub mod pbr;
+pub mod textured;
 pub mod torus;
 pub mod transformed;
@@ -0,0 +1,157 @@
+use crate::common::*;
+
+use crate::object::{ChessBoard, Object, Rectangle, Sphere};
+use crate::scene::{Scene, SceneBuilder};
+use crate::shader::{
+    self, Anisotropic, Conductor, Diffuse, Layered, MetallicRoughness,
+    OrenNayar, Sheen, ShaderType, Sum, Translucent,
+};
+
+pub fn scene() -> Scene {
+    let mut scene = SceneBuilder::default()
+        .vp_plane(Plane::new(
+            V3([0.0, 0.0, -2.0]), // r0
+            V3([0.0, 0.0, -1.0]), // n
+        ))
+        .vp_width(2.0)
+        .vp_height(2.0)
+        .camera(V3::zero())
+        .projection(Projection::Perspective)
+        .ambient(Color::White * 0.3)
+        .background_light(Color([0.05; 3]))
+        .light_falloff(Falloff::InverseSquare)
+        .max_depth(8)
+        .build()
+        .unwrap();
+
+    scene.add_white_light(V3([-5.0, 10.0, 0.0]), 60.0);
+    scene.add_emissive_object(
+        Sphere {
+            c: V3([3.0, 4.0, -6.0]),
+            r: 0.8,
+        },
+        Color::White,
+        4.0,
+        8,
+    );
+
+    // metallic increases from left to right, roughness from front to back
+    for m in 0..4 {
+        for r in 0..2 {
+            let metallic = m as f32 / 3.0;
+            let roughness = 0.15 + r as f32 * 0.5;
+            let shader =
+                MetallicRoughness::new(Color([0.9, 0.6, 0.2]).into(),
+                                       metallic.into(),
+                                       roughness.into());
+            let sphere = Sphere {
+                c: V3([m as f32 * 1.6 - 2.4, -1.0, -6.0 - r as f32 * 2.0]),
+                r: 0.6,
+            };
+            scene.add_object(sphere.shaded(shader.into()));
+        }
+    }
+
+    // wax, red light travels further under the surface
+    let wax = shader::subsurface_solid(Color([0.9, 0.7, 0.5]),
+                                       Color([0.6, 0.3, 0.15]),
+                                       20.0);
+    scene.add_object(
+        Sphere {
+            c: V3([-2.0, 0.6, -7.5]),
+            r: 0.8,
+        }
+        .shaded(wax),
+    );
+
+    // velvet, rough diffuse with sheen at the silhouette
+    let clay: ShaderType =
+        OrenNayar::new(Color([0.5, 0.05, 0.1]).into(), 0.6.into()).into();
+    let sheen: ShaderType =
+        Sheen::new(Color([1.0, 0.6, 0.7]).into(), 0.3.into()).into();
+    scene.add_object(
+        Sphere {
+            c: V3([2.0, 0.6, -7.5]),
+            r: 0.8,
+        }
+        .shaded(Sum::new(clay, sheen).into()),
+    );
+
+    // polished metals with complex IOR fresnel
+    let metals = [
+        shader::gold(),
+        shader::silver(),
+        shader::copper(),
+        shader::aluminum(),
+        shader::metal(Conductor::COPPER, 20.0),
+    ];
+    for (k, metal) in metals.iter().enumerate() {
+        let sphere = Sphere {
+            c: V3([k as f32 * 1.0 - 2.0, -1.2, -3.8]),
+            r: 0.35,
+        };
+        scene.add_object(sphere.shaded(metal.clone()));
+    }
+
+    // red car paint and varnished wood, both under a clear coat
+    scene.add_object(
+        Sphere {
+            c: V3([-3.0, 1.8, -9.0]),
+            r: 0.8,
+        }
+        .shaded(shader::car_paint(Color([0.6, 0.02, 0.02]))),
+    );
+    let wood: ShaderType =
+        OrenNayar::new(Color([0.4, 0.2, 0.08]).into(), 0.3.into()).into();
+    let varnish = Layered::new(wood, 1.5.into())
+        .roughness(0.2.into(), 4)
+        .tint(Color([1.0, 0.85, 0.6]).into());
+    scene.add_object(
+        Sphere {
+            c: V3([3.0, 1.8, -9.0]),
+            r: 0.8,
+        }
+        .shaded(varnish.into()),
+    );
+
+    // brushed metal, brushed around the vertical axis
+    let brushed = Anisotropic::new(
+        Color([0.95, 0.93, 0.88]).into(),
+        0.4.into(),
+        0.8.into(),
+    )
+    .tangent(V3([0.0, 1.0, 0.0]).into());
+    scene.add_object(
+        Sphere {
+            c: V3([0.0, 1.8, -9.0]),
+            r: 0.8,
+        }
+        .shaded(brushed.into()),
+    );
+
+    // leaf, back-lit by the emissive sphere
+    let front: ShaderType =
+        Diffuse::new(Color([0.1, 0.3, 0.05]).into()).into();
+    let back: ShaderType =
+        Translucent::new(Color([0.3, 0.6, 0.1]).into()).into();
+    scene.add_object(
+        Rectangle::new(
+            V3([0.0, 0.0, -8.0]),
+            V3([1.0, 0.0, -8.0]),
+            V3([1.0, 1.2, -8.0]),
+        )
+        .double_sided(true)
+        .shaded(Sum::new(front, back).into()),
+    );
+
+    scene.add_object(ChessBoard {
+        plane: Plane::new(V3([0.0, -1.6, 0.0]), V3([0.0, 1.0, 0.0])),
+        material: (
+            shader::simple_solid(Color([0.2; 3])),
+            shader::simple_solid(Color([0.6; 3])),
+        ),
+        ..ChessBoard::default()
+    });
+
+    scene
+}
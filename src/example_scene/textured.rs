@@ -0,0 +1,63 @@
+use crate::common::*;
+
+use crate::object::{Object, Rectangle, Sphere};
+use crate::scene::{Scene, SceneBuilder};
+use crate::shader::{Diffuse, Texture};
+
+use image::{Rgb, RgbImage};
+
+pub fn scene() -> Scene {
+    let mut scene = SceneBuilder::default()
+        .vp_plane(Plane::new(
+            V3([0.0, 0.0, -2.0]), // r0
+            V3([0.0, 0.0, -1.0]), // n
+        ))
+        .vp_width(2.0)
+        .vp_height(2.0)
+        .camera(V3::zero())
+        .projection(Projection::Perspective)
+        .ambient(Color::White * 0.3)
+        .background_light(Color([0.3; 3]))
+        .max_depth(10)
+        .build()
+        .unwrap();
+
+    scene.add_white_light(V3([-5.0, 10.0, 0.0]), 0.8);
+
+    // a test grid stands for an image loaded with `Texture::open`, colors
+    // of 8-bit images are usually sRGB encoded
+    let c = V3([0.0, 0.0, -5.0]);
+    let globe = Texture::from_image(uv_grid(256, 128, 16), ColorSpace::Srgb);
+    let shader = Diffuse::new(globe.spherical(c));
+    scene.add_object(Sphere { c, r: 1.2 }.shaded(shader.into()));
+
+    let tiles = Texture::from_image(uv_grid(64, 64, 4), ColorSpace::Srgb);
+    let floor = Rectangle::new(
+        V3([-6.0, -1.6, -2.0]),
+        V3([6.0, -1.6, -2.0]),
+        V3([6.0, -1.6, -14.0]),
+    )
+    .double_sided(true)
+    .shaded(
+        Diffuse::new(tiles.planar(
+            V3([0.0, -1.6, 0.0]),
+            V3([2.0, 0.0, 0.0]),
+            V3([0.0, 0.0, -2.0]),
+        ))
+        .into(),
+    );
+    scene.add_object(floor);
+
+    scene
+}
+
+// checkerboard of n x n cells tinted red along u and green along v
+fn uv_grid(w: u32, h: u32, n: u32) -> RgbImage {
+    RgbImage::from_fn(w, h, |x, y| {
+        let (i, j) = (x * n / w, y * n / h);
+        let light = if (i + j) % 2 == 0 { 255 } else { 160 };
+        let r = (light * (i + 1) / n) as u8;
+        let g = (light * (n - j) / n) as u8;
+        Rgb([r, g, (light / 2) as u8])
+    })
+}
@@ -12,6 +12,7 @@ extern crate derive_builder;
 
 mod common;
 mod example_scene;
+mod medium;
 mod obj_model;
 mod object;
 mod scene;
@@ -28,6 +29,6 @@ fn main() {
         .h(1024)
         .build()
         .unwrap();
-    let img = tracer::modular::trace(scene, conf);
-    img.save("./trace.png").unwrap();
+    let film = tracer::modular::trace(scene, conf);
+    film.save("./trace.png").unwrap();
 }
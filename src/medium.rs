@@ -0,0 +1,153 @@
+use crate::common::*;
+use crate::scene::Scene;
+
+use std::f32::consts::PI;
+
+const BIAS: f32 = 1e-4;
+
+// Participating medium with absorption and single scattering of the scene
+// lights, integrated by ray marching.
+#[derive(Debug, Clone)]
+pub struct Medium {
+    // absorption and scattering coefficients, per unit distance
+    pub absorption: Color,
+    pub scattering: Color,
+    // Henyey-Greenstein asymmetry in (-1, 1), positive for forward
+    // scattering, 0 for isotropic
+    pub g: f32,
+    pub density: Density,
+    // ray marching steps per segment
+    pub steps: usize,
+    // length of the segment marched for rays escaping to the background
+    pub max_distance: f32,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub enum Density {
+    Homogeneous,
+    // density varying with fractal noise of the given frequency
+    Noise { scale: f32, octaves: usize },
+}
+
+#[allow(unused)]
+impl Medium {
+    pub fn new(absorption: Color, scattering: Color) -> Self {
+        Self {
+            absorption,
+            scattering,
+            g: 0.0,
+            density: Density::Homogeneous,
+            steps: 32,
+            max_distance: 100.0,
+        }
+    }
+
+    pub fn anisotropy(mut self, g: f32) -> Self {
+        self.g = g;
+        self
+    }
+
+    pub fn noise(mut self, scale: f32, octaves: usize) -> Self {
+        self.density = Density::Noise { scale, octaves };
+        self
+    }
+
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    pub fn max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn density_at(&self, p: V3) -> f32 {
+        match self.density {
+            Density::Homogeneous => 1.0,
+            Density::Noise { scale, octaves } => fbm(p * scale, octaves),
+        }
+    }
+
+    fn extinction(&self) -> Color {
+        self.absorption + self.scattering
+    }
+
+    // Henyey-Greenstein phase function, cos is the cosine between the
+    // propagation directions before and after scattering
+    pub fn phase(&self, cos: f32) -> f32 {
+        let g = self.g;
+        let d = 1.0 + g * g - 2.0 * g * cos;
+        (1.0 - g * g) / (4.0 * PI * d * d.sqrt())
+    }
+
+    // fraction of light passing straight from a to b
+    pub fn transmittance(&self, a: V3, b: V3) -> Color {
+        let len = dist(a, b);
+        let density = match self.density {
+            Density::Homogeneous => 1.0,
+            Density::Noise { .. } => {
+                // a few samples are enough for shadows
+                let n = 4;
+                let avg: f32 = (0..n)
+                    .map(|k| (k as f32 + rand01()) / n as f32)
+                    .map(|t| self.density_at(a + (b - a) * t))
+                    .sum();
+                avg / n as f32
+            }
+        };
+        (self.extinction() * (-len * density)).map(f32::exp)
+    }
+
+    // Radiance arriving at the origin of the ray after travelling `len`
+    // through the medium, where `behind` is the radiance entering the far
+    // end. Lights are scattered once towards the ray, scaled by PI like the
+    // surface shaders (see `Diffuse`).
+    //
+    // The light reaching the medium is attenuated by `Scene::transmittance`,
+    // the medium must be bounded by a closed object whose shader accounts
+    // for it on the shadow rays leaving it, or be the fog of the scene.
+    pub fn integrate(
+        &self,
+        s: &Scene,
+        ray: &Ray,
+        len: f32,
+        behind: Color,
+    ) -> Color {
+        let steps = self.steps.max(1);
+        let dt = len / steps as f32;
+        let jitter = rand01();
+        let mut transmittance = Color::White;
+        let mut scattered = Color::Black;
+
+        for k in 0..steps {
+            let p = ray.orig + ray.dir * ((k as f32 + jitter) * dt);
+            let density = self.density_at(p);
+            let sigma_s = self.scattering * density;
+            let step = (self.extinction() * (-density * dt)).map(f32::exp);
+
+            if sigma_s != Color::Black {
+                let radiance = self.in_scattered(s, ray, p);
+                scattered = scattered + transmittance * sigma_s * radiance * dt;
+            }
+            transmittance = transmittance * step;
+        }
+
+        behind * transmittance + scattered
+    }
+
+    // light from the scene lights scattered at p towards the ray origin
+    fn in_scattered(&self, s: &Scene, ray: &Ray, p: V3) -> Color {
+        let mut radiance = Color::Black;
+        for light in s.light_samples(p, ray.wavelength) {
+            let to_light = light.pos - p;
+            let shadowray = ray.scattered(p, to_light).biased(BIAS);
+            let shadow = s.transmittance(&shadowray, to_light.dot(to_light));
+
+            let phase = self.phase(to_light.norm().dot(ray.dir));
+            radiance = radiance + light.irradiance * shadow * (phase * PI);
+        }
+        radiance
+    }
+}
@@ -36,20 +36,28 @@ impl Object for ChessBoard {
     }
 
     fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
-        let p = self.map_to_2d(i.hit.pos);
+        self.material_at(i.hit.pos).get(s, i)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        self.material_at(i.hit.pos).albedo(s, i)
+    }
+}
+
+impl ChessBoard {
+    fn material_at(&self, pos: V3) -> &ShaderType {
+        let p = self.map_to_2d(pos);
         let is_even = |v: f32| (v / self.cell_size) as i32 % 2 == 0;
         let a = is_even(p.x()) ^ (p.x() < 0.0);
         let b = is_even(p.y()) ^ (p.y() < 0.0);
 
         if a ^ b {
-            self.material.0.get(s, i)
+            &self.material.0
         } else {
-            self.material.1.get(s, i)
+            &self.material.1
         }
     }
-}
 
-impl ChessBoard {
     // returning V3 must have .z() == 0.0
     fn map_to_2d(&self, p: V3) -> V3 {
         let rp = p - self.plane.r0();
@@ -19,6 +19,8 @@ pub struct TrigMesh {
 
 pub struct TrigMeshCache {
     cluster: Cluster,
+    // trigs with their cumulative area, for sampling points on the surface
+    surface: Vec<(Trig, f32)>,
 }
 
 enum ClusterKind {
@@ -98,7 +100,14 @@ impl TrigMesh {
         let trigs = self.trigs().collect::<Vec<_>>();
         let cluster = Cluster::from_trigs(trigs.as_slice());
 
-        *self.cache.borrow_mut() = Some(TrigMeshCache { cluster });
+        let mut total_area = 0.0;
+        let mut surface = Vec::new();
+        for t in trigs.iter() {
+            total_area += t.trig().area();
+            surface.push((*t.trig(), total_area));
+        }
+
+        *self.cache.borrow_mut() = Some(TrigMeshCache { cluster, surface });
 
         Ref::map(self.cache.borrow(), |x| x.as_ref().unwrap())
     }
@@ -112,6 +121,27 @@ impl Object for TrigMesh {
         let cache = self.get_cache();
         cache.cluster.intersect(ray)
     }
+
+    fn sample_surface(&self) -> Option<(V3, V3)> {
+        let cache = self.get_cache();
+        let total_area = cache.surface.last()?.1;
+        // pick a trig with probability proportional to its area
+        let x = rand01() * total_area;
+        let idx = cache
+            .surface
+            .binary_search_by(|(_, a)| {
+                use std::cmp::Ordering;
+                a.partial_cmp(&x).unwrap_or(Ordering::Less)
+            })
+            .unwrap_or_else(|i| i);
+        let trig = cache.surface[idx.min(cache.surface.len() - 1)].0;
+        Some((trig.sample_point(), trig.n()))
+    }
+
+    fn area(&self) -> Option<f32> {
+        let cache = self.get_cache();
+        cache.surface.last().map(|(_, a)| *a)
+    }
 }
 
 impl Transform for TrigMesh {
@@ -10,6 +10,14 @@ pub trait Object {
     fn render(&self, _s: &Scene, _i: &Incidence<'_, '_, '_>) -> Option<Color> {
         Some(Color::Blue)
     }
+    // fraction of light passing through the object at the hit, for shadows
+    fn transmittance(&self, _s: &Scene, _i: &Incidence<'_, '_, '_>) -> Color {
+        Color::Black
+    }
+    // base color at the hit, for the albedo pass
+    fn albedo(&self, _s: &Scene, _i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        None
+    }
 
     // implement these two functions for accelerating computation if necessary
     // back-face bulling
@@ -21,6 +29,16 @@ pub trait Object {
         None
     }
 
+    // implement these two functions to allow the object to be used as a light
+    // uniformly samples a point on the surface, returns position and normal
+    fn sample_surface(&self) -> Option<(V3, V3)> {
+        None
+    }
+    // surface area
+    fn area(&self) -> Option<f32> {
+        None
+    }
+
     // Assign shader to object
     fn shaded(self, shader: ShaderType) -> Shaded
     where
@@ -24,7 +24,19 @@ impl Object for Shaded {
     fn const_normal(&self) -> Option<V3> {
         self.object.const_normal()
     }
+    fn sample_surface(&self) -> Option<(V3, V3)> {
+        self.object.sample_surface()
+    }
+    fn area(&self) -> Option<f32> {
+        self.object.area()
+    }
     fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
         self.shader.get(s, i)
     }
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        self.shader.transmittance(s, i)
+    }
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        self.shader.albedo(s, i)
+    }
 }
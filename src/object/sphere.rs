@@ -1,4 +1,5 @@
 use super::{Hit, Object, Ray, V3};
+use crate::common::rand_unit_v3;
 
 #[derive(Debug, Clone)]
 pub struct Sphere {
@@ -12,13 +13,15 @@ impl Object for Sphere {
         let l = self.c - ray.orig;
         let tc = l.dot(ray.dir);
         let mut inside = false;
+        let r2 = self.r * self.r;
 
-        if tc < 0.0 {
+        // rays from inside pointing away from the center still hit the
+        // sphere
+        if tc < 0.0 && l.dot(l) > r2 {
             return None;
         }
 
         let d2 = l.dot(l) - tc * tc;
-        let r2 = self.r * self.r;
 
         if d2 > r2 {
             return None;
@@ -46,4 +49,13 @@ impl Object for Sphere {
             None
         }
     }
+
+    fn sample_surface(&self) -> Option<(V3, V3)> {
+        let n = rand_unit_v3();
+        Some((self.c + n * self.r, n))
+    }
+
+    fn area(&self) -> Option<f32> {
+        Some(4.0 * std::f32::consts::PI * self.r * self.r)
+    }
 }
@@ -47,6 +47,14 @@ impl Object for Transformed {
         self.obj.render(s, &i)
     }
 
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        self.obj.transmittance(s, &i)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        self.obj.albedo(s, &i)
+    }
+
     // implement this method to allow back-face bulling
     fn const_normal(&self) -> Option<V3> {
         self.obj.const_normal()
@@ -56,4 +64,17 @@ impl Object for Transformed {
         // TODO: transform bound as well
         None
     }
+
+    fn sample_surface(&self) -> Option<(V3, V3)> {
+        let (pos, norm) = self.obj.sample_surface()?;
+        let pos = self.trans.o2w.transform_point(pos);
+        let norm = self.trans.w2o.transpose().transform_vector(norm).norm();
+        Some((pos, norm))
+    }
+
+    // exact for uniform scaling, an approximation otherwise
+    fn area(&self) -> Option<f32> {
+        let det = self.trans.o2w.det3().abs();
+        self.obj.area().map(|a| a * det.powf(2.0 / 3.0))
+    }
 }
@@ -44,6 +44,14 @@ impl Object for Triangle {
             Some(self.trig.n())
         }
     }
+
+    fn sample_surface(&self) -> Option<(V3, V3)> {
+        Some((self.trig.sample_point(), self.trig.n()))
+    }
+
+    fn area(&self) -> Option<f32> {
+        Some(self.trig.area())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -88,4 +96,17 @@ impl<'a> Object for Rectangle {
             Some(self.t1.trig.n())
         }
     }
+
+    fn sample_surface(&self) -> Option<(V3, V3)> {
+        // both halves have the same area
+        if rand01() < 0.5 {
+            self.t1.sample_surface()
+        } else {
+            self.t2.sample_surface()
+        }
+    }
+
+    fn area(&self) -> Option<f32> {
+        Some(self.t1.trig.area() * 2.0)
+    }
 }
@@ -1,6 +1,14 @@
 use crate::common::*;
+use crate::medium::Medium;
 use crate::object::Object;
 
+// shifts light samples on emissive objects towards the shading point so the
+// emissive object does not shadow itself
+const LIGHT_BIAS: f32 = 1e-3;
+const SHADOW_BIAS: f32 = 1e-4;
+// give up and consider a shadow ray blocked after this many surfaces
+const MAX_SHADOW_HITS: usize = 16;
+
 #[derive(Builder)]
 pub struct Scene {
     #[builder(setter(skip))]
@@ -14,10 +22,18 @@ pub struct Scene {
     pub ambient: Color,
     #[builder(setter(skip))]
     pub lights: Vec<PointLight>,
+    #[builder(setter(skip))]
+    pub emissive_lights: Vec<EmissiveLight>,
     #[builder(default = "Color([0.2;3])")]
     pub background_light: Color,
     #[builder(default = "5")]
     pub max_depth: usize,
+    // falloff assigned to lights added with `add_light`
+    #[builder(default = "Falloff::Constant")]
+    pub light_falloff: Falloff,
+    // global fog filling the whole scene
+    #[builder(default = "None")]
+    pub fog: Option<Medium>,
 }
 
 impl Scene {
@@ -37,9 +53,153 @@ impl Scene {
             pos,
             color,
             brightness,
+            falloff: self.light_falloff,
+            spectrum: None,
+        })
+    }
+
+    // adds a light emitting the given spectrum, which is used in spectral
+    // mode while its RGB projection is used otherwise
+    #[allow(unused)]
+    pub fn add_spectral_light(
+        &mut self,
+        pos: V3,
+        spectrum: Spectrum,
+        brightness: f32,
+    ) {
+        self.lights.push(PointLight {
+            pos,
+            color: spectrum.to_rgb(),
+            brightness,
+            falloff: self.light_falloff,
+            spectrum: Some(spectrum),
         })
     }
 
+    // Adds a glowing object which is also sampled as an area light, with
+    // `samples` shadow rays per shading point
+    #[allow(unused)]
+    pub fn add_emissive_object<T: 'static>(
+        &mut self,
+        obj: T,
+        color: Color,
+        strength: f32,
+        samples: usize,
+    ) where
+        T: Object + Sized,
+    {
+        use crate::shader::Emission;
+
+        let emission = Emission::new(color.into(), strength.into());
+        self.emissive_lights.push(EmissiveLight {
+            obj: self.objs.len(),
+            radiance: color * strength,
+            samples,
+        });
+        self.add_object(obj.shaded(emission.into()))
+    }
+
+    // samples all light sources for direct illumination of point p, at the
+    // wavelength carried by the ray in spectral mode
+    pub fn light_samples(
+        &self,
+        p: V3,
+        wavelength: Option<f32>,
+    ) -> Vec<LightSample> {
+        let mut samples = self.point_light_samples(p, wavelength);
+
+        for light in self.emissive_lights.iter() {
+            let obj = &self.objs[light.obj];
+            let area = match obj.area() {
+                Some(area) => area,
+                None => continue,
+            };
+
+            for _ in 0..light.samples {
+                let (pos, norm) = match obj.sample_surface() {
+                    Some(sample) => sample,
+                    None => break,
+                };
+                let d = p - pos;
+                let dist2 = d.dot(d).max(1e-6);
+                let cos_l = norm.dot(d.norm()).abs();
+                let pdf = light.samples as f32 / area;
+                samples.push(LightSample {
+                    pos: pos + d.norm() * LIGHT_BIAS,
+                    irradiance: light.radiance * (cos_l / dist2 / pdf),
+                });
+            }
+        }
+
+        samples
+    }
+
+    fn point_light_samples(
+        &self,
+        p: V3,
+        wavelength: Option<f32>,
+    ) -> Vec<LightSample> {
+        self.lights
+            .iter()
+            .map(|light| LightSample {
+                pos: light.pos,
+                irradiance: light.irradiance_at(p, wavelength),
+            })
+            .collect()
+    }
+
+    // light samples reaching p, attenuated by the objects in between
+    pub fn visible_light_samples(
+        &self,
+        p: V3,
+        wavelength: Option<f32>,
+    ) -> Vec<LightSample> {
+        self.shadowed(p, self.light_samples(p, wavelength), wavelength)
+    }
+
+    // Point lights reaching p, for shaders which already see the emissive
+    // objects through the rays they trace
+    pub fn visible_point_light_samples(
+        &self,
+        p: V3,
+        wavelength: Option<f32>,
+    ) -> Vec<LightSample> {
+        let samples = self.point_light_samples(p, wavelength);
+        self.shadowed(p, samples, wavelength)
+    }
+
+    // shadow rays carry the wavelength, so transparent objects filter it
+    fn shadowed(
+        &self,
+        p: V3,
+        mut samples: Vec<LightSample>,
+        wavelength: Option<f32>,
+    ) -> Vec<LightSample> {
+        for light in samples.iter_mut() {
+            let shadowray = Ray {
+                wavelength,
+                ..Ray::new(p, light.pos - p).biased(SHADOW_BIAS)
+            };
+            let shadow = self.transmittance(&shadowray, dist2(light.pos, p));
+            light.irradiance = light.irradiance * shadow;
+        }
+
+        samples
+    }
+
+    // direct irradiance from all lights received by a surface at p facing n,
+    // accounting for shadows
+    pub fn irradiance(&self, p: V3, n: V3, wavelength: Option<f32>) -> Color {
+        let mut intensity = Color::Black;
+
+        for light in self.visible_light_samples(p, wavelength) {
+            let angle = (light.pos - p).norm().dot(n).max(0.0);
+            intensity = intensity + light.irradiance * angle;
+        }
+
+        intensity
+    }
+
     pub fn vp_from_pixel(&self, x: f32, y: f32, w: f32, h: f32) -> V3 {
         let dx = self.vp_width * 2.0 / w;
         // y on screen coordinate system is inverted, down is positive
@@ -61,14 +221,31 @@ impl Scene {
     }
 
     pub fn trace_ray(&self, ray: &Ray, d: usize) -> Option<Color> {
+        self.trace(ray, d, false)
+    }
+
+    // Traces a ray sampled by a shader which lights its surface from the
+    // light samples already. Objects sampled as lights are seen black, so
+    // their light is not counted twice.
+    pub fn trace_indirect(&self, ray: &Ray, d: usize) -> Option<Color> {
+        self.trace(ray, d, true)
+    }
+
+    fn trace(&self, ray: &Ray, d: usize, skip_lights: bool) -> Option<Color> {
         use crate::shader::Incidence;
         if d >= self.max_depth {
             return None;
         }
 
-        match self.nearest_hit(ray) {
-            None => Some(self.ambient),
-            Some((obj, hit)) => {
+        let is_light =
+            |id: usize| self.emissive_lights.iter().any(|l| l.obj == id);
+        let (color, len) = match self.nearest_hit_id(ray) {
+            None => (Some(self.ambient), None),
+            Some((id, hit)) if skip_lights && is_light(id) => {
+                (Some(Color::Black), Some(dist(hit.pos, ray.orig)))
+            }
+            Some((id, hit)) => {
+                let obj = &self.objs[id];
                 let inci = Incidence {
                     ray: &ray,
                     obj: obj.as_ref(),
@@ -76,27 +253,72 @@ impl Scene {
                     trans: None,
                     depth: d,
                 };
-                obj.render(self, &inci)
+                (obj.render(self, &inci), Some(dist(hit.pos, ray.orig)))
+            }
+        };
+
+        match &self.fog {
+            None => color,
+            Some(fog) => {
+                let len = len.unwrap_or(fog.max_distance);
+                color.map(|c| fog.integrate(self, ray, len, c))
             }
         }
     }
 
-    pub fn is_blocked(&self, ray: &Ray, light_dist2: f32) -> bool {
-        match self.nearest_hit(ray) {
-            None => false,
-            Some((_, h)) => dist2(h.pos, ray.orig) < light_dist2,
+    // Fraction of light reaching the point at light_dist2 along the ray.
+    // Opaque objects block the ray while transparent ones attenuate it.
+    pub fn transmittance(&self, ray: &Ray, light_dist2: f32) -> Color {
+        use crate::shader::Incidence;
+        let end = ray.orig + ray.dir * light_dist2.sqrt();
+        let mut ray = *ray;
+        // the fog attenuates the whole way to the light
+        let mut result = match &self.fog {
+            None => Color::White,
+            Some(fog) => fog.transmittance(ray.orig, end),
+        };
+
+        for depth in 0..MAX_SHADOW_HITS {
+            let (obj, hit) = match self.nearest_hit(&ray) {
+                None => return result,
+                Some(x) => x,
+            };
+            if dist2(hit.pos, ray.orig) >= dist2(end, ray.orig) {
+                return result;
+            }
+
+            let inci = Incidence {
+                ray: &ray,
+                obj: obj.as_ref(),
+                hit: &hit,
+                trans: None,
+                depth,
+            };
+            result = result * obj.transmittance(self, &inci);
+            if result == Color::Black {
+                return result;
+            }
+
+            ray = ray.scattered(hit.pos, ray.dir).biased(SHADOW_BIAS);
         }
+
+        Color::Black
     }
 
     pub fn nearest_hit<'a>(
         &'a self,
         ray: &Ray,
     ) -> Option<(&'a Box<dyn Object>, Hit)> {
+        self.nearest_hit_id(ray).map(|(id, hit)| (&self.objs[id], hit))
+    }
+
+    // nearest hit with the index of the object hit
+    pub fn nearest_hit_id(&self, ray: &Ray) -> Option<(usize, Hit)> {
         use std::f32;
         let mut min_dist = f32::INFINITY;
         let mut result = None;
 
-        for obj in self.objs.iter() {
+        for (id, obj) in self.objs.iter().enumerate() {
             // back-face bulling for optimizing rendering speed
             if let Some(n) = obj.const_normal() {
                 if ray.dir.dot(n) > 0.0 {
@@ -113,7 +335,7 @@ impl Scene {
                 if dist2(hit.pos, ray.orig) > min_dist {
                     continue;
                 }
-                result = Some((obj, hit));
+                result = Some((id, hit));
                 min_dist = dist2(hit.pos, ray.orig);
             }
         }
@@ -0,0 +1,172 @@
+use crate::common::{Color, V3};
+use crate::scene::Scene;
+use crate::shader::microfacet::*;
+use crate::shader::{DynValue, Incidence, Shader};
+
+// Anisotropic GGX specular, for brushed metal and hair-like surfaces with
+// highlights stretched across the brushing direction. Only the specular
+// lobe is rendered, add a diffuse shader with `Sum` for dielectrics.
+//
+// Lighting is scaled by PI like `MetallicRoughness`.
+pub struct Anisotropic {
+    // reflectance at normal incidence
+    pub color: DynValue<Color>,
+    pub roughness: DynValue<f32>,
+    // in (-1, 1), 0 is isotropic
+    pub anisotropy: DynValue<f32>,
+    // rotation of the tangent around the normal, in radians
+    pub rotation: DynValue<f32>,
+    // brushing direction in world space, projected on the surface. An
+    // arbitrary tangent is used if not set.
+    pub tangent: Option<DynValue<V3>>,
+}
+
+const BIAS: f32 = 1e-4;
+
+// tangent frame and parameters evaluated at a hit
+struct Params {
+    t: V3,
+    b: V3,
+    n: V3,
+    v: V3,
+    ax: f32,
+    ay: f32,
+    f0: Color,
+}
+
+impl Params {
+    fn to_local(&self, w: V3) -> V3 {
+        V3([w.dot(self.t), w.dot(self.b), w.dot(self.n)])
+    }
+
+    fn to_world(&self, w: V3) -> V3 {
+        (self.t * w.x() + self.b * w.y() + self.n * w.z()).norm()
+    }
+}
+
+impl Anisotropic {
+    pub fn new(
+        color: DynValue<Color>,
+        roughness: DynValue<f32>,
+        anisotropy: DynValue<f32>,
+    ) -> Self {
+        Self {
+            color,
+            roughness,
+            anisotropy,
+            rotation: 0.0.into(),
+            tangent: None,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn rotation(mut self, rotation: DynValue<f32>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    #[allow(unused)]
+    pub fn tangent(mut self, tangent: DynValue<V3>) -> Self {
+        self.tangent = Some(tangent);
+        self
+    }
+
+    fn params(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Params {
+        let v = -i.ray.dir;
+        let mut n = i.hit.norm;
+        if n.dot(v) < 0.0 {
+            n = -n;
+        }
+
+        // Gram-Schmidt the tangent hint against the normal
+        let t = match &self.tangent {
+            Some(hint) => {
+                let hint = hint.get(s, i);
+                let t = hint - n * hint.dot(n);
+                if t.dot(t) > 1e-8 {
+                    t.norm()
+                } else {
+                    tangent_frame(n).0
+                }
+            }
+            None => tangent_frame(n).0,
+        };
+        let rotation = self.rotation.get(s, i);
+        let t = t * rotation.cos() + n.cross(t) * rotation.sin();
+
+        let (ax, ay) = alpha_aniso(
+            self.roughness.get(s, i),
+            self.anisotropy.get(s, i),
+        );
+        Params {
+            t,
+            b: n.cross(t),
+            n,
+            v,
+            ax,
+            ay,
+            f0: self.color.get(s, i),
+        }
+    }
+
+    // BRDF times cosine term for light coming from l, scaled by PI
+    fn eval(&self, p: &Params, l: V3) -> Color {
+        let n_dot_l = p.n.dot(l);
+        let n_dot_v = p.n.dot(p.v).max(1e-4);
+        if n_dot_l <= 0.0 {
+            return Color::Black;
+        }
+
+        let h = (l + p.v).norm();
+        let f = fresnel_schlick(p.f0, p.v.dot(h));
+        let d = ggx_d_aniso(p.to_local(h), p.ax, p.ay);
+        let g = smith_g1_aniso(p.to_local(l), p.ax, p.ay)
+            * smith_g1_aniso(p.to_local(p.v), p.ax, p.ay);
+
+        f * (std::f32::consts::PI * d * g / (4.0 * n_dot_v))
+    }
+
+    fn sample(&self, p: &Params) -> Option<(V3, Color)> {
+        let h = p.to_world(sample_ggx_aniso(p.ax, p.ay));
+        let l = reflect(p.v, h);
+        let n_dot_l = p.n.dot(l);
+        if n_dot_l <= 0.0 {
+            return None;
+        }
+
+        let n_dot_v = p.n.dot(p.v).max(1e-4);
+        let n_dot_h = p.n.dot(h).max(1e-4);
+        let g = smith_g1_aniso(p.to_local(l), p.ax, p.ay)
+            * smith_g1_aniso(p.to_local(p.v), p.ax, p.ay);
+        let f = fresnel_schlick(p.f0, p.v.dot(h));
+        Some((l, f * (p.v.dot(h).abs() * g / (n_dot_v * n_dot_h))))
+    }
+}
+
+impl Shader for Anisotropic {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let p = self.params(s, i);
+        let hit = i.hit;
+        let mut color = Color::Black;
+
+        for light in s.visible_light_samples(hit.pos, i.ray.wavelength) {
+            let l = (light.pos - hit.pos).norm();
+            color = color + self.eval(&p, l) * light.irradiance;
+        }
+
+        // reflection, with a single importance sampled ray. Emissive objects
+        // are left to the direct lighting.
+        if let Some((l, weight)) = self.sample(&p) {
+            let ray = i.ray.scattered(hit.pos + p.n * BIAS, l);
+            if let Some(c) = s.trace_indirect(&ray, i.depth + 1) {
+                color = color + c * weight;
+            }
+        }
+
+        Some(color)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        Some(self.color.get(s, i))
+    }
+}
@@ -0,0 +1,22 @@
+use crate::common::Color;
+use crate::scene::Scene;
+use crate::shader::{DynValue, Incidence, Shader};
+
+// Glowing surface, emits color * strength regardless of the lighting.
+// Use `Scene::add_emissive_object` to let it illuminate other objects.
+pub struct Emission {
+    pub color: DynValue<Color>,
+    pub strength: DynValue<f32>,
+}
+
+impl Emission {
+    pub fn new(color: DynValue<Color>, strength: DynValue<f32>) -> Self {
+        Self { color, strength }
+    }
+}
+
+impl Shader for Emission {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        Some(self.color.get(s, i) * self.strength.get(s, i))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::common::Color;
+use crate::scene::Scene;
+use crate::shader::microfacet::{alpha, fresnel_schlick, ggx_d, smith_g};
+use crate::shader::transparent::fresnel;
+use crate::shader::{
+    DynValue, GlossyReflection, Incidence, Reflection, Shader, ShaderType,
+};
+
+// Dielectric coat over a base shader, for car paint and varnished wood.
+//
+// The coat reflects a Fresnel weighted part of the light, only the rest
+// reaches the base, tinted by the coat on its way in and out.
+pub struct Layered {
+    base: ShaderType,
+    ior: DynValue<f32>,
+    roughness: DynValue<f32>,
+    // color of the light after passing through the coat and back
+    tint: DynValue<Color>,
+    reflection: ShaderType,
+    frac: DynValue<f32>,
+}
+
+impl Layered {
+    // smooth clear coat
+    pub fn new(base: ShaderType, ior: DynValue<f32>) -> Self {
+        Self {
+            base,
+            frac: fresnel(&ior),
+            ior,
+            roughness: 0.0.into(),
+            tint: Color::White.into(),
+            reflection: Reflection.into(),
+        }
+    }
+
+    // coat reflecting over a GGX lobe, averaging `samples` rays
+    #[allow(unused)]
+    pub fn roughness(
+        mut self,
+        roughness: DynValue<f32>,
+        samples: usize,
+    ) -> Self {
+        self.reflection =
+            GlossyReflection::new(roughness.clone(), samples).into();
+        self.roughness = roughness;
+        self
+    }
+
+    #[allow(unused)]
+    pub fn tint(mut self, tint: DynValue<Color>) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    // highlights of the point lights on the coat, scaled by PI like
+    // `MetallicRoughness`. Emissive objects are reflected by the coat
+    // already.
+    fn highlights(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        let ior = self.ior.get(s, i);
+        let a = alpha(self.roughness.get(s, i));
+        let f0 = ((ior - 1.0) / (ior + 1.0)).powi(2);
+        let v = -i.ray.dir;
+        let n = if i.hit.norm.dot(v) < 0.0 {
+            -i.hit.norm
+        } else {
+            i.hit.norm
+        };
+        let n_dot_v = n.dot(v).max(1e-4);
+
+        let mut color = Color::Black;
+        let p = i.hit.pos;
+        for light in s.visible_point_light_samples(p, i.ray.wavelength) {
+            let l = (light.pos - i.hit.pos).norm();
+            let n_dot_l = n.dot(l);
+            if n_dot_l <= 0.0 {
+                continue;
+            }
+            let h = (l + v).norm();
+            let f = fresnel_schlick(Color::from_intensity(f0), v.dot(h));
+            let d = ggx_d(n.dot(h), a);
+            let g = smith_g(n_dot_l, n_dot_v, a);
+            let spec = std::f32::consts::PI * d * g / (4.0 * n_dot_v);
+            color = color + f * light.irradiance * spec;
+        }
+        color
+    }
+}
+
+impl Shader for Layered {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let f = self.frac.get(s, i);
+        let tint = self.tint.get(s, i);
+        let base = self.base.get(s, i)? * tint * (1.0 - f);
+        let coat = self.reflection.get(s, i)? * f;
+        Some(base + coat + self.highlights(s, i))
+    }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        let f = self.frac.get(s, i);
+        self.base.transmittance(s, i) * self.tint.get(s, i) * (1.0 - f)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        Some(self.base.albedo(s, i)? * self.tint.get(s, i))
+    }
+}
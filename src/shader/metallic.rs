@@ -0,0 +1,140 @@
+use crate::common::{Color, V3};
+use crate::scene::Scene;
+use crate::shader::microfacet::*;
+use crate::shader::{DynValue, Incidence, Shader};
+
+// Cook-Torrance GGX BSDF with metallic-roughness parameterization.
+//
+// Lighting follows the convention of `Diffuse`, where a white lambertian
+// surface reflects all the irradiance it receives, so the BRDF is scaled
+// by PI.
+pub struct MetallicRoughness {
+    pub base_color: DynValue<Color>,
+    pub metallic: DynValue<f32>,
+    pub roughness: DynValue<f32>,
+    // reflectance of dielectrics, 0.5 means 4% at normal incidence
+    pub specular: DynValue<f32>,
+    // shading normal in world space, defaults to the geometric normal
+    pub normal: Option<DynValue<V3>>,
+}
+
+const BIAS: f32 = 1e-4;
+
+// parameters evaluated at a hit
+struct Params {
+    n: V3,
+    v: V3,
+    alpha: f32,
+    f0: Color,
+    diffuse: Color,
+}
+
+impl MetallicRoughness {
+    pub fn new(
+        base_color: DynValue<Color>,
+        metallic: DynValue<f32>,
+        roughness: DynValue<f32>,
+    ) -> Self {
+        Self {
+            base_color,
+            metallic,
+            roughness,
+            specular: 0.5.into(),
+            normal: None,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn specular(mut self, specular: DynValue<f32>) -> Self {
+        self.specular = specular;
+        self
+    }
+
+    #[allow(unused)]
+    pub fn normal(mut self, normal: DynValue<V3>) -> Self {
+        self.normal = Some(normal);
+        self
+    }
+
+    fn params(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Params {
+        let base = self.base_color.get(s, i);
+        let metallic = self.metallic.get(s, i).max(0.0).min(1.0);
+        let specular = self.specular.get(s, i);
+        let v = -i.ray.dir;
+        let mut n = match &self.normal {
+            Some(n) => n.get(s, i).norm(),
+            None => i.hit.norm,
+        };
+        // always shade the side facing the viewer
+        if n.dot(v) < 0.0 {
+            n = -n;
+        }
+
+        let f0 = Color::from_intensity(0.08 * specular);
+        Params {
+            n,
+            v,
+            alpha: alpha(self.roughness.get(s, i)),
+            f0: base.blend(f0, metallic),
+            diffuse: base * (1.0 - metallic),
+        }
+    }
+
+    // BRDF times cosine term for light coming from l, scaled by PI
+    fn eval(&self, p: &Params, l: V3) -> Color {
+        let n_dot_l = p.n.dot(l);
+        let n_dot_v = p.n.dot(p.v).max(1e-4);
+        if n_dot_l <= 0.0 {
+            return Color::Black;
+        }
+
+        let h = (l + p.v).norm();
+        let f = fresnel_schlick(p.f0, p.v.dot(h));
+        let d = ggx_d(p.n.dot(h), p.alpha);
+        let g = smith_g(n_dot_l, n_dot_v, p.alpha);
+
+        let spec = f * (std::f32::consts::PI * d * g / (4.0 * n_dot_v));
+        let diffuse = p.diffuse * (Color::White - f) * n_dot_l;
+        spec + diffuse
+    }
+
+    fn sample_specular(&self, p: &Params) -> Option<(V3, Color)> {
+        let h = sample_ggx(p.n, p.alpha);
+        let l = reflect(p.v, h);
+        if p.n.dot(l) <= 0.0 {
+            return None;
+        }
+
+        let f = fresnel_schlick(p.f0, p.v.dot(h));
+        Some((l, f * sampled_weight(p.n, p.v, h, l, p.alpha)))
+    }
+}
+
+impl Shader for MetallicRoughness {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let p = self.params(s, i);
+        let hit = i.hit;
+        let mut color = p.diffuse * s.background_light;
+
+        // direct lighting
+        for light in s.visible_light_samples(hit.pos, i.ray.wavelength) {
+            let l = (light.pos - hit.pos).norm();
+            color = color + self.eval(&p, l) * light.irradiance;
+        }
+
+        // reflection, with a single importance sampled ray. Emissive objects
+        // are left to the direct lighting.
+        if let Some((l, weight)) = self.sample_specular(&p) {
+            let ray = i.ray.scattered(hit.pos + p.n * BIAS, l);
+            if let Some(c) = s.trace_indirect(&ray, i.depth + 1) {
+                color = color + c * weight;
+            }
+        }
+
+        Some(color)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        Some(self.base_color.get(s, i))
+    }
+}
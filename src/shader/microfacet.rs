@@ -0,0 +1,130 @@
+// GGX (Trowbridge-Reitz) microfacet model helpers
+//
+// See: https://www.cs.cornell.edu/~srm/publications/EGSR07-btdf.pdf
+use crate::common::{rand01, Color, V3};
+use std::f32::consts::PI;
+
+// perceptual roughness to GGX alpha
+pub fn alpha(roughness: f32) -> f32 {
+    (roughness * roughness).max(1e-3)
+}
+
+// normal distribution function
+pub fn ggx_d(n_dot_h: f32, alpha: f32) -> f32 {
+    if n_dot_h <= 0.0 {
+        return 0.0;
+    }
+    let a2 = alpha * alpha;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    a2 / (PI * d * d)
+}
+
+// Smith masking function for one direction
+pub fn smith_g1(n_dot_x: f32, alpha: f32) -> f32 {
+    if n_dot_x <= 0.0 {
+        return 0.0;
+    }
+    let a2 = alpha * alpha;
+    let c2 = n_dot_x * n_dot_x;
+    2.0 / (1.0 + (1.0 + a2 * (1.0 - c2) / c2).sqrt())
+}
+
+// separable Smith masking-shadowing function
+pub fn smith_g(n_dot_l: f32, n_dot_v: f32, alpha: f32) -> f32 {
+    smith_g1(n_dot_l, alpha) * smith_g1(n_dot_v, alpha)
+}
+
+pub fn fresnel_schlick(f0: Color, cos: f32) -> Color {
+    let k = (1.0 - cos.max(0.0).min(1.0)).powi(5);
+    f0 + (Color::White - f0) * k
+}
+
+// orthonormal tangent and bitangent perpendicular to n, continuous
+// everywhere but across the z = 0 plane
+// See: https://jcgt.org/published/0006/01/01/
+pub fn tangent_frame(n: V3) -> (V3, V3) {
+    let (x, y, z) = (n.x(), n.y(), n.z());
+    let sign = if z >= 0.0 { 1.0 } else { -1.0 };
+    let a = -1.0 / (sign + z);
+    let b = x * y * a;
+    let t = V3([1.0 + sign * x * x * a, sign * b, -sign * x]);
+    let bt = V3([b, sign + y * y * a, -y]);
+    (t, bt)
+}
+
+fn to_world(n: V3, local: V3) -> V3 {
+    let (t, b) = tangent_frame(n);
+    (t * local.x() + b * local.y() + n * local.z()).norm()
+}
+
+// samples a microfacet normal around n, distributed as D(h) * (n.h)
+pub fn sample_ggx(n: V3, alpha: f32) -> V3 {
+    let (u1, u2) = (rand01(), rand01());
+    let cos2 = (1.0 - u1) / (1.0 + (alpha * alpha - 1.0) * u1);
+    let cos = cos2.sqrt();
+    let sin = (1.0 - cos2).max(0.0).sqrt();
+    let phi = 2.0 * PI * u2;
+    to_world(n, V3([sin * phi.cos(), sin * phi.sin(), cos]))
+}
+
+// samples a direction around n, distributed as (n.l) / PI
+pub fn sample_cosine(n: V3) -> V3 {
+    let (u1, u2) = (rand01(), rand01());
+    let r = u1.sqrt();
+    let phi = 2.0 * PI * u2;
+    let z = (1.0 - u1).max(0.0).sqrt();
+    to_world(n, V3([r * phi.cos(), r * phi.sin(), z]))
+}
+
+// anisotropic GGX alphas along the tangent and the bitangent, anisotropy
+// in (-1, 1) roughens along the bitangent when positive, which stretches
+// the highlight along the bitangent, across the tangent
+pub fn alpha_aniso(roughness: f32, anisotropy: f32) -> (f32, f32) {
+    let a = roughness * roughness;
+    let an = anisotropy.max(-0.99).min(0.99);
+    ((a * (1.0 - an)).max(1e-3), (a * (1.0 + an)).max(1e-3))
+}
+
+// anisotropic normal distribution function, h in the local tangent frame
+pub fn ggx_d_aniso(h: V3, ax: f32, ay: f32) -> f32 {
+    if h.z() <= 0.0 {
+        return 0.0;
+    }
+    let (x, y) = (h.x() / ax, h.y() / ay);
+    let d = x * x + y * y + h.z() * h.z();
+    1.0 / (PI * ax * ay * d * d)
+}
+
+// anisotropic Smith masking function, w in the local tangent frame
+pub fn smith_g1_aniso(w: V3, ax: f32, ay: f32) -> f32 {
+    if w.z() <= 0.0 {
+        return 0.0;
+    }
+    let (x, y) = (w.x() * ax, w.y() * ay);
+    let a2 = (x * x + y * y) / (w.z() * w.z());
+    2.0 / (1.0 + (1.0 + a2).sqrt())
+}
+
+// samples a microfacet normal in the local tangent frame, distributed as
+// D(h) * h.z, by stretching isotropic slopes
+pub fn sample_ggx_aniso(ax: f32, ay: f32) -> V3 {
+    let (u1, u2) = (rand01().min(0.9999), rand01());
+    let r = (u1 / (1.0 - u1)).sqrt();
+    let phi = 2.0 * PI * u2;
+    V3([-ax * r * phi.cos(), -ay * r * phi.sin(), 1.0]).norm()
+}
+
+// mirrors v about n, both pointing away from the surface
+pub fn reflect(v: V3, n: V3) -> V3 {
+    n * (2.0 * v.dot(n)) - v
+}
+
+// Weight (bsdf * cos / pdf) of a direction l scattered through the
+// microfacet normal m sampled by `sample_ggx`, for both reflection and
+// refraction, excluding the fresnel term.
+pub fn sampled_weight(n: V3, v: V3, m: V3, l: V3, alpha: f32) -> f32 {
+    let n_dot_v = n.dot(v).abs().max(1e-4);
+    let n_dot_m = n.dot(m).abs().max(1e-4);
+    let g = smith_g(n.dot(l).abs(), n_dot_v, alpha);
+    v.dot(m).abs() * g / (n_dot_v * n_dot_m)
+}
@@ -27,6 +27,33 @@ impl Shader for Mix {
             Some(left.blend(right, f))
         }
     }
+
+    // the fraction may be costly to shade, it is skipped when both
+    // shaders are opaque
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        let left = self.a.transmittance(s, i);
+        let right = self.b.transmittance(s, i);
+        if left == Color::Black && right == Color::Black {
+            return Color::Black;
+        }
+
+        let f = self.frac.get(s, i);
+        if f <= 0.0 {
+            right
+        } else if f >= 1.0 {
+            left
+        } else {
+            left.blend(right, f)
+        }
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let f = self.frac.get(s, i);
+        match (self.a.albedo(s, i), self.b.albedo(s, i)) {
+            (Some(a), Some(b)) => Some(a.blend(b, f.max(0.0).min(1.0))),
+            (a, b) => a.or(b),
+        }
+    }
 }
 
 pub struct ChannelMix {
@@ -54,6 +81,25 @@ impl Shader for ChannelMix {
             Some(left.channel_blend(right, frac))
         }
     }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        let left = self.a.transmittance(s, i);
+        let right = self.b.transmittance(s, i);
+        if left == Color::Black && right == Color::Black {
+            return Color::Black;
+        }
+
+        let frac = self.frac.get(s, i).regularize();
+        left.channel_blend(right, frac)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let frac = self.frac.get(s, i).regularize();
+        match (self.a.albedo(s, i), self.b.albedo(s, i)) {
+            (Some(a), Some(b)) => Some(a.channel_blend(b, frac)),
+            (a, b) => a.or(b),
+        }
+    }
 }
 
 #[allow(unused)]
@@ -75,4 +121,17 @@ impl Shader for Sum {
         let b = self.b.get(s, i)?;
         Some(a + b)
     }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        let a = self.a.transmittance(s, i);
+        let b = self.b.transmittance(s, i);
+        (a + b).regularize()
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        match (self.a.albedo(s, i), self.b.albedo(s, i)) {
+            (Some(a), Some(b)) => Some((a + b).regularize()),
+            (a, b) => a.or(b),
+        }
+    }
 }
@@ -1,37 +1,72 @@
-use crate::common::{Color, Hit, Ray, TransMat};
+use crate::common::{Color, Hit, Ray, Spectrum, TransMat};
 use crate::object::Object;
 use crate::scene::Scene;
 
 use std::rc::Rc;
 
 pub mod diffuse;
+pub mod oren_nayar;
 pub mod phong;
 pub mod plain;
 pub mod reflection;
 pub mod refraction;
+pub mod sheen;
+pub mod thin_film;
+pub mod translucent;
 
 pub use self::diffuse::Diffuse;
+pub use self::oren_nayar::OrenNayar;
 pub use self::phong::Phong;
 pub use self::plain::Plain;
-pub use self::reflection::Reflection;
-pub use self::refraction::Refraction;
+pub use self::reflection::{GlossyReflection, Reflection};
+pub use self::refraction::{
+    DispersiveRefraction, GlossyRefraction, Refraction,
+};
+pub use self::sheen::Sheen;
+pub use self::thin_film::ThinFilm;
+pub use self::translucent::Translucent;
 
 pub mod normal;
 pub use self::normal::Normal;
 
+pub mod emission;
+pub use self::emission::Emission;
+
+pub mod volume;
+pub use self::volume::Volume;
+
+pub mod subsurface;
+pub use self::subsurface::Subsurface;
+
+pub mod anisotropic;
+pub mod layered;
+pub mod metallic;
+pub mod microfacet;
+pub use self::anisotropic::Anisotropic;
+pub use self::layered::Layered;
+pub use self::metallic::MetallicRoughness;
+
+pub mod texture;
+pub use self::texture::Texture;
+
 pub mod color_noise;
 pub mod rough;
 pub use self::color_noise::ColorNoise;
 pub use self::rough::Rough;
 
 pub mod transparent;
-pub use self::transparent::{transparent, Transparency};
+pub use self::transparent::{Conductor, Transparency};
 
 pub mod preset;
 pub mod simple;
-pub use self::preset::{blank, glass, mirror, rough_solid, solid};
+pub use self::preset::{
+    aluminum, blank, car_paint, colored_glass, copper, dispersive_glass,
+    frosted_glass, glass, gold, metal, mirror, rough_solid, silver,
+    soap_bubble, solid, subsurface_solid,
+};
 pub use self::simple::{
-    simple_glass, simple_mirror, simple_rough_solid, simple_solid,
+    simple_frosted_glass, simple_glass, simple_mirror, simple_rough_solid,
+    simple_solid,
 };
 
 pub mod mix;
@@ -45,11 +80,61 @@ pub struct Incidence<'r, 'h, 'o> {
     pub depth: usize,
 }
 
+// Number of rays for shaders sampling a distribution. Only the primary hit
+// is sampled `samples` times, deeper bounces shoot a single ray to keep the
+// ray count from growing exponentially.
+pub fn samples_at_depth(samples: usize, depth: usize) -> usize {
+    if depth == 0 {
+        samples.max(1)
+    } else {
+        1
+    }
+}
+
 pub trait Shader {
     fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color>;
+
+    // fraction of light passing through the surface, used to attenuate
+    // shadow rays (Black: opaque, White: fully transparent)
+    fn transmittance(&self, _s: &Scene, _i: &Incidence<'_, '_, '_>) -> Color {
+        Color::Black
+    }
+
+    // Base color of the surface for the albedo pass and the denoiser. None
+    // for shaders without one, like mirrors.
+    fn albedo(&self, _s: &Scene, _i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        None
+    }
 }
 
-pub type ShaderType = DynValue<Option<Color>>;
+#[derive(Clone)]
+pub struct ShaderType(Rc<dyn Shader>);
+
+impl ShaderType {
+    pub fn get(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        self.0.render(s, i)
+    }
+
+    pub fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        self.0.transmittance(s, i)
+    }
+
+    pub fn albedo(
+        &self,
+        s: &Scene,
+        i: &Incidence<'_, '_, '_>,
+    ) -> Option<Color> {
+        self.0.albedo(s, i)
+    }
+
+    pub fn map<F, U>(self, f: F) -> DynValue<U>
+    where
+        F: Fn(Option<Color>) -> U,
+        F: 'static,
+    {
+        DynValue::from_fn(move |s: &Scene, i: &Incidence<'_, '_, '_>| f(self.get(s, i)))
+    }
+}
 
 #[derive(Clone)]
 pub enum DynValue<T> {
@@ -103,7 +188,19 @@ where
     T: Shader + 'static,
 {
     fn from(v: T) -> ShaderType {
-        DynValue::from_fn(move |s: &Scene, i: &Incidence<'_, '_, '_>| v.render(s, i))
+        ShaderType(Rc::new(v))
+    }
+}
+
+impl From<DynValue<Option<Color>>> for ShaderType {
+    fn from(v: DynValue<Option<Color>>) -> ShaderType {
+        DynValueShader(v).into()
+    }
+}
+
+impl From<Option<Color>> for ShaderType {
+    fn from(v: Option<Color>) -> ShaderType {
+        DynValueShader(v.into()).into()
     }
 }
 
@@ -113,8 +210,21 @@ impl<T> From<T> for DynValue<T> {
     }
 }
 
-#[allow(unused)]
-struct DynValueShader(ShaderType);
+// In spectral mode evaluates the spectrum at the wavelength of the ray, as a
+// gray color. Uses the RGB projection otherwise.
+impl From<Spectrum> for DynValue<Color> {
+    fn from(spectrum: Spectrum) -> DynValue<Color> {
+        let rgb = spectrum.to_rgb();
+        DynValue::from_fn(move |_s: &Scene, i: &Incidence<'_, '_, '_>| {
+            match i.ray.wavelength {
+                Some(l) => Color::from_intensity(spectrum.at(l)),
+                None => rgb,
+            }
+        })
+    }
+}
+
+struct DynValueShader(DynValue<Option<Color>>);
 
 impl Shader for DynValueShader {
     fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
@@ -129,7 +239,7 @@ impl DynValue<Color> {
     }
 }
 
-impl ShaderType {
+impl DynValue<Option<Color>> {
     #[allow(unused)]
     fn into_shader(self) -> DynValueShader {
         DynValueShader(self)
@@ -0,0 +1,64 @@
+use crate::common::Color;
+use crate::scene::Scene;
+use crate::shader::{DynValue, Incidence, Shader};
+
+// Oren-Nayar rough diffuse, for clay, plaster and sand. Rough surfaces
+// scatter more light back towards the light source, making them look
+// flatter than Lambertian ones. A roughness of 0 is equivalent to `Diffuse`.
+//
+// See: https://www1.cs.columbia.edu/CAVE/publications/pdfs/Oren_SIGGRAPH94.pdf
+pub struct OrenNayar {
+    pub color: DynValue<Color>,
+    // standard deviation of the facet slopes, in radians
+    pub roughness: DynValue<f32>,
+}
+
+impl OrenNayar {
+    pub fn new(color: DynValue<Color>, roughness: DynValue<f32>) -> Self {
+        Self { color, roughness }
+    }
+}
+
+impl Shader for OrenNayar {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let color = self.color.get(s, i);
+        let sigma2 = self.roughness.get(s, i).powi(2);
+        let a = 1.0 - 0.5 * sigma2 / (sigma2 + 0.33);
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+        let Incidence { hit, .. } = i;
+        let n = hit.norm;
+        let v = -i.ray.dir;
+        let cos_r = n.dot(v).max(0.0);
+        let sin_r = (1.0 - cos_r * cos_r).max(0.0).sqrt();
+        // view direction projected on the tangent plane
+        let v_t = v - n * cos_r;
+
+        let mut intensity = s.background_light;
+        for light in s.visible_light_samples(hit.pos, i.ray.wavelength) {
+            let l = (light.pos - hit.pos).norm();
+            let cos_i = n.dot(l);
+            if cos_i <= 0.0 {
+                continue;
+            }
+            let sin_i = (1.0 - cos_i * cos_i).max(0.0).sqrt();
+            let l_t = l - n * cos_i;
+
+            // cos(phi_i - phi_r) * sin(alpha) * tan(beta)
+            let cos_phi = if sin_i * sin_r > 1e-4 {
+                (l_t.dot(v_t) / (sin_i * sin_r)).max(0.0)
+            } else {
+                0.0
+            };
+            let k = cos_phi * sin_i * sin_r / cos_i.max(cos_r).max(1e-4);
+
+            intensity = intensity + light.irradiance * (cos_i * (a + b * k));
+        }
+
+        Some(color * intensity)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        Some(self.color.get(s, i))
+    }
+}
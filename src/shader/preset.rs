@@ -1,5 +1,8 @@
-use crate::common::Color;
-use crate::shader::{ChannelMix, Diffuse, Mix, Phong, Reflection, Rough, ShaderType};
+use crate::common::{Color, Dispersion};
+use crate::shader::{
+    ChannelMix, Diffuse, DynValue, Layered, MetallicRoughness, Mix, Phong,
+    Reflection, Refraction, Rough, ShaderType, Subsurface, Sum,
+};
 
 #[allow(unused)]
 pub fn blank() -> ShaderType {
@@ -18,6 +21,26 @@ pub fn solid(color: Color, specular_index: f32) -> ShaderType {
     .into()
 }
 
+// subsurface scattering material with specular highlights, for skin, wax
+// and marble. radius is the distance light travels under the surface.
+#[allow(unused)]
+pub fn subsurface_solid(
+    color: Color,
+    radius: Color,
+    specular_index: f32,
+) -> ShaderType {
+    let subsurface: ShaderType =
+        Subsurface::new(color.into(), radius.into()).into();
+    let phong: ShaderType = Phong::new(specular_index.into()).into();
+
+    ChannelMix::new(
+        Some(Color::White).into(),
+        subsurface,
+        phong.map(|x| x.unwrap()),
+    )
+    .into()
+}
+
 #[allow(unused)]
 pub fn rough_solid(
     color: Color,
@@ -36,7 +59,10 @@ pub fn rough_solid(
     Rough::new(shader, roughness).into()
 }
 
-use super::transparent;
+use super::transparent::{
+    absorption_for, fresnel_conductor, fresnel_thin_film, Conductor,
+};
+use super::Transparency;
 
 pub fn glass(
     color: Color,
@@ -45,11 +71,103 @@ pub fn glass(
     reflectivity: f32,
     ior: f32,
 ) -> ShaderType {
-    let trans = transparent(reflectivity, ior);
+    // the glass color tints the light passing through and the shadows
+    let trans = Transparency::tinted(reflectivity, ior.into(), color.into());
     let solid_ = solid(color, specular_index);
     Mix::new(trans.into(), solid_.into(), transparency.into()).into()
 }
 
+// Clear glass colored by absorption in the volume, thick parts of the
+// object look darker. White light turns into `color` after travelling
+// `distance` inside.
+#[allow(unused)]
+pub fn colored_glass(
+    color: Color,
+    distance: f32,
+    reflectivity: f32,
+    ior: f32,
+) -> ShaderType {
+    let absorption = absorption_for(color, distance);
+    Transparency::new(reflectivity, ior.into())
+        .with_absorption(absorption.into())
+        .into()
+}
+
+#[allow(unused)]
+pub fn dispersive_glass(
+    dispersion: Dispersion,
+    reflectivity: f32,
+) -> ShaderType {
+    Transparency::dispersive(reflectivity, dispersion).into()
+}
+
+#[allow(unused)]
+pub fn frosted_glass(
+    color: Color,
+    specular_index: f32,
+    transparency: f32,
+    reflectivity: f32,
+    ior: f32,
+    roughness: f32,
+    samples: usize,
+) -> ShaderType {
+    let (ior, roughness) = (ior.into(), roughness.into());
+    let tint = color.into();
+    let trans =
+        Transparency::glossy(reflectivity, ior, roughness, tint, samples);
+    let solid_ = solid(color, specular_index);
+    Mix::new(trans.into(), solid_.into(), transparency.into()).into()
+}
+
+// Soap film, transparent with iridescent reflections. The thickness in nm
+// is usually a few hundreds, varying over the bubble.
+#[allow(unused)]
+pub fn soap_bubble(thickness: DynValue<f32>) -> ShaderType {
+    let frac = fresnel_thin_film(&1.0.into(), &1.33.into(), &thickness);
+    let refl: ShaderType = Reflection.into();
+    // the film is too thin to bend the light passing through
+    let refr: ShaderType = Refraction::new(1.0.into()).into();
+    ChannelMix::new(refl, refr, frac).into()
+}
+
+// Polished metal, reflections and highlights tinted by the conductor
+// fresnel term
+#[allow(unused)]
+pub fn metal(conductor: Conductor, specular_index: f32) -> ShaderType {
+    let refl: ShaderType = Reflection.into();
+    let phong: ShaderType = Phong::new(specular_index.into()).into();
+    let black: ShaderType = Some(Color::Black).into();
+    let shine = Sum::new(refl, phong).into();
+    ChannelMix::new(shine, black, fresnel_conductor(conductor)).into()
+}
+
+#[allow(unused)]
+pub fn gold() -> ShaderType {
+    metal(Conductor::GOLD, 200.0)
+}
+
+#[allow(unused)]
+pub fn silver() -> ShaderType {
+    metal(Conductor::SILVER, 200.0)
+}
+
+#[allow(unused)]
+pub fn copper() -> ShaderType {
+    metal(Conductor::COPPER, 200.0)
+}
+
+#[allow(unused)]
+pub fn aluminum() -> ShaderType {
+    metal(Conductor::ALUMINUM, 200.0)
+}
+
+// metallic flake paint under a clear coat
+#[allow(unused)]
+pub fn car_paint(color: Color) -> ShaderType {
+    let paint = MetallicRoughness::new(color.into(), 0.6.into(), 0.4.into());
+    Layered::new(paint.into(), 1.5.into()).into()
+}
+
 pub fn mirror(
     color: Color,
     specular_index: f32,
@@ -1,6 +1,7 @@
 use crate::common::Color;
 use crate::scene::Scene;
-use crate::shader::{Incidence, Shader};
+use crate::shader::microfacet::{alpha, reflect, sample_ggx, sampled_weight};
+use crate::shader::{samples_at_depth, DynValue, Incidence, Shader};
 
 const BIAS: f32 = 1e-5;
 
@@ -13,3 +14,47 @@ impl Shader for Reflection {
         s.trace_ray(&ray, i.depth + 1)
     }
 }
+
+// Reflection spread over a GGX lobe, averaging `samples` rays
+pub struct GlossyReflection {
+    pub roughness: DynValue<f32>,
+    pub samples: usize,
+}
+
+impl GlossyReflection {
+    pub fn new(roughness: DynValue<f32>, samples: usize) -> Self {
+        Self { roughness, samples }
+    }
+}
+
+impl Shader for GlossyReflection {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let a = alpha(self.roughness.get(s, i));
+        let v = -i.ray.dir;
+        // normal on the side of the incoming ray
+        let n = if i.hit.norm.dot(v) < 0.0 {
+            -i.hit.norm
+        } else {
+            i.hit.norm
+        };
+        let orig = i.hit.pos + n * BIAS;
+
+        let samples = samples_at_depth(self.samples, i.depth);
+        let mut colors = Vec::with_capacity(samples);
+        for _ in 0..samples {
+            let m = sample_ggx(n, a);
+            let l = reflect(v, m);
+            if l.dot(n) <= 0.0 {
+                // reflected into the surface
+                colors.push(Color::Black);
+                continue;
+            }
+
+            let ray = i.ray.scattered(orig, l);
+            let c = s.trace_ray(&ray, i.depth + 1)?;
+            colors.push(c * sampled_weight(n, v, m, l, a));
+        }
+
+        Some(Color::average(&colors))
+    }
+}
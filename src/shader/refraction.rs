@@ -1,12 +1,25 @@
-use crate::common::Color;
+use crate::common::{Color, Dispersion, Hit, RGB_WAVELENGTHS, V3};
 use crate::scene::Scene;
-use crate::shader::{DynValue, Incidence, Shader};
+use crate::shader::microfacet::{alpha, sample_ggx, sampled_weight};
+use crate::shader::{samples_at_depth, DynValue, Incidence, Shader};
 
 const BIAS: f32 = 1e-5;
 
 #[derive(Clone)]
 pub struct Refraction {
     pub ior: DynValue<f32>,
+    // color filter applied to the light passing through
+    pub tint: DynValue<Color>,
+}
+
+impl Refraction {
+    pub fn new(ior: DynValue<f32>) -> Self {
+        Self::tinted(ior, Color::White.into())
+    }
+
+    pub fn tinted(ior: DynValue<f32>, tint: DynValue<Color>) -> Self {
+        Self { ior, tint }
+    }
 }
 
 impl Shader for Refraction {
@@ -16,6 +29,128 @@ impl Shader for Refraction {
         // let ior = if i.hit.inside { ior } else { 1.0 / ior };
         let bias = if i.hit.inside { BIAS } else { -BIAS };
         let ray = i.ray.refract(&i.hit.biased(bias), ior);
-        s.trace_ray(&ray, i.depth + 1)
+        let tint = self.tint.get(s, i);
+        s.trace_ray(&ray, i.depth + 1).map(|c| c * tint)
+    }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        self.tint.get(s, i)
+    }
+}
+
+// Refraction through a rough interface, with the microfacet normals
+// distributed over a GGX lobe. `samples` rays are averaged.
+pub struct GlossyRefraction {
+    pub ior: DynValue<f32>,
+    pub roughness: DynValue<f32>,
+    pub tint: DynValue<Color>,
+    pub samples: usize,
+}
+
+impl GlossyRefraction {
+    #[allow(unused)]
+    pub fn new(
+        ior: DynValue<f32>,
+        roughness: DynValue<f32>,
+        samples: usize,
+    ) -> Self {
+        Self::tinted(ior, roughness, Color::White.into(), samples)
+    }
+
+    pub fn tinted(
+        ior: DynValue<f32>,
+        roughness: DynValue<f32>,
+        tint: DynValue<Color>,
+        samples: usize,
+    ) -> Self {
+        Self {
+            ior,
+            roughness,
+            tint,
+            samples,
+        }
+    }
+}
+
+impl Shader for GlossyRefraction {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let ior = self.ior.get(s, i);
+        let a = alpha(self.roughness.get(s, i));
+        let v = -i.ray.dir;
+        let n = i.hit.norm;
+        let bias = if i.hit.inside { BIAS } else { -BIAS };
+
+        let samples = samples_at_depth(self.samples, i.depth);
+        let mut colors = Vec::with_capacity(samples);
+        for _ in 0..samples {
+            // microfacet normal, on the same side as the surface normal
+            let m = sample_ggx(n, a);
+            // total internal reflection on the microfacet, the ray is
+            // reflected back on the incoming side
+            let ray = if total_reflection(i.ray.dir, m, ior) {
+                i.ray.reflect(&Hit { norm: m, ..i.hit.biased(-bias) })
+            } else {
+                i.ray.refract(&Hit { norm: m, ..i.hit.biased(bias) }, ior)
+            };
+            let c = s.trace_ray(&ray, i.depth + 1)?;
+            colors.push(c * sampled_weight(n, v, m, ray.dir, a));
+        }
+
+        let tint = self.tint.get(s, i);
+        Some(Color::average(&colors) * tint)
+    }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        self.tint.get(s, i)
+    }
+}
+
+// whether a ray along dir hitting a surface of normal n has no refracted
+// direction, the same test as `Ray::refract`
+fn total_reflection(dir: V3, n: V3, ior: f32) -> bool {
+    let cosi = n.dot(dir).max(-1.0).min(1.0);
+    let eta = if cosi < 0.0 { 1.0 / ior } else { ior };
+    1.0 - eta * eta * (1.0 - cosi * cosi) < 0.0
+}
+
+// Refraction with the IOR varying per wavelength. A ray carrying all
+// wavelengths is split into one ray per color channel, each continuing
+// with its own wavelength.
+pub struct DispersiveRefraction {
+    pub dispersion: Dispersion,
+    pub tint: DynValue<Color>,
+}
+
+impl DispersiveRefraction {
+    pub fn new(dispersion: Dispersion) -> Self {
+        Self {
+            dispersion,
+            tint: Color::White.into(),
+        }
+    }
+}
+
+impl Shader for DispersiveRefraction {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let bias = if i.hit.inside { BIAS } else { -BIAS };
+        let hit = i.hit.biased(bias);
+        let tint = self.tint.get(s, i);
+
+        if let Some(wavelength) = i.ray.wavelength {
+            let ray = i.ray.refract(&hit, self.dispersion.ior(wavelength));
+            return s.trace_ray(&ray, i.depth + 1).map(|c| c * tint);
+        }
+
+        let mut color = Color::Black;
+        for (k, wavelength) in RGB_WAVELENGTHS.iter().enumerate() {
+            let ray = i.ray.with_wavelength(*wavelength);
+            let ray = ray.refract(&hit, self.dispersion.ior(*wavelength));
+            color.0[k] = s.trace_ray(&ray, i.depth + 1)?.0[k];
+        }
+        Some(color * tint)
+    }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        self.tint.get(s, i)
     }
 }
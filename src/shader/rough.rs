@@ -23,4 +23,12 @@ impl Shader for Rough {
         let i = Incidence { hit, ..*i };
         self.shader.get(s, &i)
     }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        self.shader.transmittance(s, i)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        self.shader.albedo(s, i)
+    }
 }
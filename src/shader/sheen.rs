@@ -0,0 +1,56 @@
+use crate::common::Color;
+use crate::scene::Scene;
+use crate::shader::{DynValue, Incidence, Shader};
+
+use std::f32::consts::PI;
+
+// Sheen lobe for cloth like velvet and satin, light scattered by fibers at
+// grazing angles. Only the sheen is rendered, add it on top of a diffuse
+// shader with `Sum`.
+//
+// Uses the "Charlie" distribution with Neubelt's visibility term.
+// See: https://blog.selfshadow.com/publications/s2017-shading-course/imageworks/s2017_pbs_imageworks_sheen.pdf
+pub struct Sheen {
+    pub color: DynValue<Color>,
+    // in (0, 1], lower values concentrate the sheen at the silhouette
+    pub roughness: DynValue<f32>,
+}
+
+impl Sheen {
+    pub fn new(color: DynValue<Color>, roughness: DynValue<f32>) -> Self {
+        Self { color, roughness }
+    }
+}
+
+impl Shader for Sheen {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let color = self.color.get(s, i);
+        let r = self.roughness.get(s, i).max(0.07).min(1.0);
+
+        let Incidence { hit, .. } = i;
+        let n = hit.norm;
+        let v = -i.ray.dir;
+        let n_dot_v = n.dot(v).max(1e-4);
+
+        let mut intensity = Color::Black;
+        for light in s.visible_light_samples(hit.pos, i.ray.wavelength) {
+            let l = (light.pos - hit.pos).norm();
+            let n_dot_l = n.dot(l);
+            if n_dot_l <= 0.0 {
+                continue;
+            }
+
+            let h = (l + v).norm();
+            let cos_h = n.dot(h);
+            let sin_h2 = (1.0 - cos_h * cos_h).max(0.0);
+            let d = (2.0 + 1.0 / r) * sin_h2.powf(0.5 / r) / (2.0 * PI);
+            let vis = 1.0 / (4.0 * (n_dot_l + n_dot_v - n_dot_l * n_dot_v));
+
+            // scaled by PI like `Diffuse`
+            let brdf = d * vis * PI;
+            intensity = intensity + light.irradiance * (brdf * n_dot_l);
+        }
+
+        Some(color * intensity)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::common::Color;
 use crate::shader::ShaderType;
 
-use super::preset::{glass, mirror, rough_solid, solid};
+use super::preset::{frosted_glass, glass, mirror, rough_solid, solid};
 
 pub fn simple_solid(color: Color) -> ShaderType {
     solid(color, 10.0)
@@ -16,6 +16,14 @@ pub fn simple_glass(color: Color, transparency: f32) -> ShaderType {
     glass(color, 25.0, transparency, 0.8, 1.5)
 }
 
+pub fn simple_frosted_glass(
+    color: Color,
+    transparency: f32,
+    roughness: f32,
+) -> ShaderType {
+    frosted_glass(color, 25.0, transparency, 0.8, 1.5, roughness, 16)
+}
+
 pub fn simple_mirror(color: Color) -> ShaderType {
     mirror(color, 45.0, 0.8)
 }
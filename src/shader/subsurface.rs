@@ -0,0 +1,128 @@
+use crate::common::{rand01, rand_unit_v3, Color, V3};
+use crate::scene::Scene;
+use crate::shader::microfacet::sample_cosine;
+use crate::shader::{samples_at_depth, DynValue, Incidence, Shader};
+
+const BIAS: f32 = 1e-4;
+
+// Random walk subsurface scattering, for skin, wax and marble.
+//
+// Light enters the surface and scatters isotropically inside the object
+// until it leaves through the surface again, where it picks up the direct
+// lighting. The object should be closed, like a `Sphere` or a closed
+// `TrigMesh`, paths leaving an open mesh through a hole only see the
+// background light.
+//
+// See: https://graphics.pixar.com/library/PathTracedSubsurface/
+pub struct Subsurface {
+    // color of the surface as seen from afar
+    pub color: DynValue<Color>,
+    // mean free path per channel, how far light travels under the surface
+    pub radius: DynValue<Color>,
+    // walks per channel at the primary hit
+    pub samples: usize,
+    pub max_bounces: usize,
+}
+
+impl Subsurface {
+    pub fn new(color: DynValue<Color>, radius: DynValue<Color>) -> Self {
+        Self {
+            color,
+            radius,
+            samples: 8,
+            max_bounces: 64,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    // single scattering albedo giving the surface albedo after multiple
+    // scattering, from van de Hulst's inversion
+    fn single_scattering_albedo(albedo: f32) -> f32 {
+        let a = albedo.max(0.0).min(0.999);
+        let t = 4.097_12 + 4.208_63 * a
+                - (9.592_17 + 41.680_8 * a + 17.712_6 * a * a).sqrt();
+        1.0 - t * t
+    }
+
+    // walks a path inside the object for a single channel, returns the
+    // light leaving the object at the path's exit
+    fn walk(
+        &self,
+        s: &Scene,
+        i: &Incidence<'_, '_, '_>,
+        n: V3,
+        sigma_t: f32,
+        albedo: f32,
+        channel: usize,
+    ) -> f32 {
+        let mut pos = i.hit.pos - n * BIAS;
+        let mut dir = sample_cosine(-n);
+        let mut weight = 1.0;
+
+        for _ in 0..self.max_bounces {
+            let d = -(1.0 - rand01()).ln() / sigma_t;
+            let ray = i.ray.scattered(pos, dir);
+
+            match i.obj.intersect(&ray) {
+                // only open meshes are missed from inside, the path leaves
+                // through a hole
+                None => return weight * s.background_light.0[channel],
+                Some(exit) if (exit.pos - pos).magn() < d => {
+                    let out_n = if exit.norm.dot(dir) > 0.0 {
+                        exit.norm
+                    } else {
+                        -exit.norm
+                    };
+                    let p = exit.pos + out_n * BIAS;
+                    let wavelength = i.ray.wavelength;
+                    let light = s.background_light
+                        + s.irradiance(p, out_n, wavelength);
+                    return weight * light.0[channel];
+                }
+                Some(_) => {
+                    pos = pos + dir * d;
+                    dir = rand_unit_v3();
+                    weight *= albedo;
+                }
+            }
+        }
+
+        0.0
+    }
+}
+
+impl Shader for Subsurface {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let color = self.color.get(s, i);
+        let radius = self.radius.get(s, i);
+        let v = -i.ray.dir;
+        // outward normal
+        let n = if i.hit.norm.dot(v) < 0.0 {
+            -i.hit.norm
+        } else {
+            i.hit.norm
+        };
+
+        let samples = samples_at_depth(self.samples, i.depth);
+        let mut result = Color::Black;
+        for channel in 0..3 {
+            let sigma_t = 1.0 / radius.0[channel].max(1e-4);
+            let albedo = Self::single_scattering_albedo(color.0[channel]);
+            let sum: f32 = (0..samples)
+                .map(|_| self.walk(s, i, n, sigma_t, albedo, channel))
+                .sum();
+            result.0[channel] = sum / samples as f32;
+        }
+
+        Some(result)
+    }
+
+    fn albedo(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        Some(self.color.get(s, i))
+    }
+}
@@ -0,0 +1,63 @@
+use crate::common::{Color, ColorSpace, V3};
+use crate::scene::Scene;
+use crate::shader::{DynValue, Incidence};
+
+use image::{ImageResult, RgbImage};
+use std::f32::consts::PI;
+use std::path::Path;
+
+// Image texture, projected on the objects to give a `DynValue<Color>`.
+//
+// Color maps are usually sRGB encoded while data maps (roughness, normals)
+// are linear, the color space tells how to decode the texels.
+pub struct Texture {
+    image: RgbImage,
+    color_space: ColorSpace,
+}
+
+#[allow(unused)]
+impl Texture {
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        color_space: ColorSpace,
+    ) -> ImageResult<Self> {
+        let image = image::open(path)?.to_rgb();
+        Ok(Self { image, color_space })
+    }
+
+    pub fn from_image(image: RgbImage, color_space: ColorSpace) -> Self {
+        Self { image, color_space }
+    }
+
+    // linear color at texture coordinates, wrapping around. v goes up from
+    // the bottom of the image.
+    pub fn sample(&self, u: f32, v: f32) -> Color {
+        let (w, h) = self.image.dimensions();
+        let x = ((u - u.floor()) * w as f32) as u32;
+        let y = ((1.0 - (v - v.floor())) * h as f32) as u32;
+        let [r, g, b] = self.image.get_pixel(x.min(w - 1), y.min(h - 1)).0;
+        let c = Color([r as f32, g as f32, b as f32]) * (1.0 / 255.0);
+        self.color_space.decode(c)
+    }
+
+    // tiles the image on the plane spanned by u and v, each axis covering
+    // one repetition
+    pub fn planar(self, origin: V3, u: V3, v: V3) -> DynValue<Color> {
+        let f = move |_s: &Scene, i: &Incidence<'_, '_, '_>| {
+            let p = i.hit.pos - origin;
+            self.sample(p.dot(u) / u.dot(u), p.dot(v) / v.dot(v))
+        };
+        DynValue::from_fn(f)
+    }
+
+    // wraps the image around a sphere with the poles along the y axis
+    pub fn spherical(self, center: V3) -> DynValue<Color> {
+        let f = move |_s: &Scene, i: &Incidence<'_, '_, '_>| {
+            let d = (i.hit.pos - center).norm();
+            let u = 0.5 + d.z().atan2(d.x()) / (2.0 * PI);
+            let v = 0.5 + d.y().max(-1.0).min(1.0).asin() / PI;
+            self.sample(u, v)
+        };
+        DynValue::from_fn(f)
+    }
+}
@@ -0,0 +1,44 @@
+use crate::common::Color;
+use crate::scene::Scene;
+use crate::shader::transparent::fresnel_thin_film;
+use crate::shader::{DynValue, Incidence, Reflection, Shader, ShaderType};
+
+// Reflection modulated by thin-film interference, giving the iridescent
+// colors of oil slicks and coated lenses. See `preset::soap_bubble` for a
+// transparent film.
+pub struct ThinFilm {
+    shader: ShaderType,
+    reflectance: DynValue<Color>,
+}
+
+impl ThinFilm {
+    // film of `thickness` nm and index `film_ior` on a surface of index
+    // `ior`, reflecting the scene
+    pub fn new(
+        thickness: DynValue<f32>,
+        film_ior: DynValue<f32>,
+        ior: DynValue<f32>,
+    ) -> Self {
+        Self::wrap(Reflection.into(), thickness, film_ior, ior)
+    }
+
+    // modulates any reflection shader, like `GlossyReflection`
+    pub fn wrap(
+        shader: ShaderType,
+        thickness: DynValue<f32>,
+        film_ior: DynValue<f32>,
+        ior: DynValue<f32>,
+    ) -> Self {
+        let reflectance = fresnel_thin_film(&ior, &film_ior, &thickness);
+        Self {
+            shader,
+            reflectance,
+        }
+    }
+}
+
+impl Shader for ThinFilm {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        Some(self.shader.get(s, i)? * self.reflectance.get(s, i))
+    }
+}
@@ -0,0 +1,31 @@
+use crate::common::Color;
+use crate::scene::Scene;
+use crate::shader::{DynValue, Incidence, Shader};
+
+// Diffuse transmission for thin objects like leaves, paper and lampshades,
+// lit by the lights behind the surface. Only the back-lit side is
+// rendered, combine it with `Diffuse` using `Sum` or `Mix`.
+pub struct Translucent {
+    pub color: DynValue<Color>,
+}
+
+impl Translucent {
+    pub fn new(color: DynValue<Color>) -> Self {
+        Self { color }
+    }
+}
+
+impl Shader for Translucent {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        let color = self.color.get(s, i);
+        let Incidence { hit, .. } = i;
+        // facing the viewer, lights behind it shine through
+        let n = if hit.norm.dot(i.ray.dir) > 0.0 {
+            -hit.norm
+        } else {
+            hit.norm
+        };
+
+        Some(color * s.irradiance(hit.pos, -n, i.ray.wavelength))
+    }
+}
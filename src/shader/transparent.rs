@@ -1,7 +1,11 @@
-use crate::common::{Color, Hit, Ray};
+use crate::common::{
+    dist, Color, Dispersion, Hit, Ray, D_LINE, RGB_WAVELENGTHS,
+};
+use crate::medium::Medium;
 use crate::scene::Scene;
 use crate::shader::{
-    DynValue, Incidence, Mix, Reflection, Refraction, Shader, ShaderType,
+    DispersiveRefraction, DynValue, GlossyReflection, GlossyRefraction,
+    Incidence, Mix, Reflection, Refraction, Shader, ShaderType,
 };
 
 // return reflection ratio
@@ -37,35 +41,267 @@ pub fn fresnel(ior: &DynValue<f32>) -> DynValue<f32> {
     DynValue::from_fn(f)
 }
 
+// Complex index of refraction n + ik of a metal, per channel at
+// `RGB_WAVELENGTHS`
+#[derive(Debug, Clone, Copy)]
+pub struct Conductor {
+    pub n: Color,
+    pub k: Color,
+}
+
+#[allow(unused)]
+impl Conductor {
+    // See: https://refractiveindex.info
+    pub const GOLD: Conductor = Conductor {
+        n: Color([0.143, 0.374, 1.442]),
+        k: Color([3.983, 2.385, 1.603]),
+    };
+    pub const SILVER: Conductor = Conductor {
+        n: Color([0.155, 0.117, 0.138]),
+        k: Color([4.828, 3.122, 2.147]),
+    };
+    pub const COPPER: Conductor = Conductor {
+        n: Color([0.200, 0.924, 1.102]),
+        k: Color([3.912, 2.452, 2.142]),
+    };
+    pub const ALUMINUM: Conductor = Conductor {
+        n: Color([1.657, 0.880, 0.521]),
+        k: Color([9.224, 6.270, 4.837]),
+    };
+}
+
+// reflection ratio of a conductor, for unpolarized light
+fn fresnel_conductor_internal(cosi: f32, n: f32, k: f32) -> f32 {
+    let cos2 = cosi * cosi;
+    let sin2 = 1.0 - cos2;
+    let t = n * n - k * k - sin2;
+    let a2b2 = (t * t + 4.0 * n * n * k * k).sqrt();
+    let a = (0.5 * (a2b2 + t)).max(0.0).sqrt();
+
+    let rs = (a2b2 + cos2 - 2.0 * a * cosi) / (a2b2 + cos2 + 2.0 * a * cosi);
+    let x = a2b2 * cos2 + sin2 * sin2;
+    let y = 2.0 * a * cosi * sin2;
+    let rp = rs * (x - y) / (x + y);
+    (rs + rp) / 2.0
+}
+
+// reflection ratio of a metal per channel, or at the wavelength of the ray
+// in spectral mode
+pub fn fresnel_conductor(conductor: Conductor) -> DynValue<Color> {
+    let Conductor { n, k } = conductor;
+    let f = move |_s: &Scene, i: &Incidence<'_, '_, '_>| {
+        let cosi = i.ray.dir.dot(i.hit.norm).abs().min(1.0);
+        match i.ray.wavelength {
+            Some(l) => {
+                let (n, k) = (n.spectral_value(l), k.spectral_value(l));
+                Color::from_intensity(fresnel_conductor_internal(cosi, n, k))
+            }
+            None => {
+                n.mix_with(k, |n, k| fresnel_conductor_internal(cosi, n, k))
+            }
+        }
+    };
+    DynValue::from_fn(f)
+}
+
+// Reflectance of a thin film of thickness d (in nm) and index n2 on a
+// substrate of index n3, seen from a medium of index n1, at the given
+// wavelength. Interference of the light reflected on both sides of the film
+// makes the reflectance oscillate with wavelength and angle.
+//
+// See: https://en.wikipedia.org/wiki/Thin-film_interference
+fn thin_film_internal(
+    cos1: f32,
+    n1: f32,
+    n2: f32,
+    n3: f32,
+    d: f32,
+    wavelength: f32,
+) -> f32 {
+    use std::f32::consts::PI;
+
+    let sin1 = (1.0 - cos1 * cos1).max(0.0).sqrt();
+    let sin2 = n1 / n2 * sin1;
+    let sin3 = n1 / n3 * sin1;
+    if sin2 >= 1.0 || sin3 >= 1.0 {
+        return 1.0;
+    }
+    let cos2 = (1.0 - sin2 * sin2).sqrt();
+    let cos3 = (1.0 - sin3 * sin3).sqrt();
+
+    // amplitude coefficients of both interfaces for s and p polarizations
+    let rs12 = (n1 * cos1 - n2 * cos2) / (n1 * cos1 + n2 * cos2);
+    let rp12 = (n2 * cos1 - n1 * cos2) / (n2 * cos1 + n1 * cos2);
+    let rs23 = (n2 * cos2 - n3 * cos3) / (n2 * cos2 + n3 * cos3);
+    let rp23 = (n3 * cos2 - n2 * cos3) / (n3 * cos2 + n2 * cos3);
+
+    // phase difference of the ray travelling through the film
+    let cos_delta = (4.0 * PI * n2 * d * cos2 / wavelength).cos();
+    let airy = |r12: f32, r23: f32| {
+        let x = 2.0 * r12 * r23 * cos_delta;
+        (r12 * r12 + r23 * r23 + x) / (1.0 + r12 * r12 * r23 * r23 + x)
+    };
+    (airy(rs12, rs23) + airy(rp12, rp23)) / 2.0
+}
+
+// reflection ratio of a surface of index `ior` coated with a thin film, per
+// channel, or at the wavelength of the ray in spectral mode
+pub fn fresnel_thin_film(
+    ior: &DynValue<f32>,
+    film_ior: &DynValue<f32>,
+    thickness: &DynValue<f32>,
+) -> DynValue<Color> {
+    let (ior, film_ior) = (ior.clone(), film_ior.clone());
+    let thickness = thickness.clone();
+    let f = move |s: &Scene, i: &Incidence<'_, '_, '_>| {
+        let (n2, d) = (film_ior.get(s, i), thickness.get(s, i));
+        let cos1 = i.ray.dir.dot(i.hit.norm);
+        // the substrate is on the other side of the surface
+        let (n1, n3) = if cos1 > 0.0 {
+            (ior.get(s, i), 1.0)
+        } else {
+            (1.0, ior.get(s, i))
+        };
+        let r = |l| thin_film_internal(cos1.abs(), n1, n2, n3, d, l);
+
+        match i.ray.wavelength {
+            Some(l) => Color::from_intensity(r(l)),
+            None => Color(RGB_WAVELENGTHS).map(r),
+        }
+    };
+    DynValue::from_fn(f)
+}
+
 pub struct Transparency {
     refr: ShaderType,
     mix: ShaderType,
+    // Beer-Lambert absorption coefficient of the volume, per unit distance
+    absorption: DynValue<Color>,
+    // scattering medium filling the volume
+    medium: Option<Medium>,
 }
 
 impl Shader for Transparency {
     fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
         if i.hit.inside {
-            return self.refr.get(s, i);
+            let c = self.refr.get(s, i)? * self.attenuation(s, i);
+            if let Some(medium) = &self.medium {
+                let len = dist(i.ray.orig, i.hit.pos);
+                return Some(medium.integrate(s, i.ray, len, c));
+            }
+            return Some(c);
         } else {
             return self.mix.get(s, i);
         }
     }
+
+    fn transmittance(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        if i.hit.inside {
+            let t = self.refr.transmittance(s, i) * self.attenuation(s, i);
+            match &self.medium {
+                Some(medium) => t * medium.transmittance(i.ray.orig, i.hit.pos),
+                None => t,
+            }
+        } else {
+            self.mix.transmittance(s, i)
+        }
+    }
 }
 
 impl Transparency {
     pub fn new(reflectivity: f32, ior: DynValue<f32>) -> Self {
+        Self::tinted(reflectivity, ior, Color::White.into())
+    }
+
+    // the tint filters both the refracted light and the shadows cast
+    pub fn tinted(
+        reflectivity: f32,
+        ior: DynValue<f32>,
+        tint: DynValue<Color>,
+    ) -> Self {
+        let refl: ShaderType = Reflection.into();
+        let refr: ShaderType = Refraction::tinted(ior.clone(), tint).into();
+        Self::from_shaders(refl, refr, reflectivity, ior)
+    }
+
+    // frosted glass, reflects and refracts over a GGX lobe. The tint
+    // filters the refracted light and the shadows like `tinted`.
+    pub fn glossy(
+        reflectivity: f32,
+        ior: DynValue<f32>,
+        roughness: DynValue<f32>,
+        tint: DynValue<Color>,
+        samples: usize,
+    ) -> Self {
+        let refl: ShaderType =
+            GlossyReflection::new(roughness.clone(), samples).into();
+        let refr: ShaderType =
+            GlossyRefraction::tinted(ior.clone(), roughness, tint, samples)
+                .into();
+        Self::from_shaders(refl, refr, reflectivity, ior)
+    }
+
+    // glass splitting light into colors, like prisms and diamonds
+    pub fn dispersive(reflectivity: f32, dispersion: Dispersion) -> Self {
         let refl: ShaderType = Reflection.into();
-        let refr: ShaderType = Refraction { ior: ior.clone() }.into();
+        let refr: ShaderType = DispersiveRefraction::new(dispersion).into();
+        let ior = move |_s: &Scene, i: &Incidence<'_, '_, '_>| {
+            dispersion.ior(i.ray.wavelength.unwrap_or(D_LINE))
+        };
+        let ior = DynValue::from_fn(ior);
+        Self::from_shaders(refl, refr, reflectivity, ior)
+    }
+
+    fn from_shaders(
+        refl: ShaderType,
+        refr: ShaderType,
+        reflectivity: f32,
+        ior: DynValue<f32>,
+    ) -> Self {
         let frac = fresnel(&ior);
 
         let mix =
             Mix::new(refl, refr.clone(), frac.map(move |f| f * reflectivity))
                 .into();
 
-        Self { refr, mix }
+        let absorption = Color::Black.into();
+        Self {
+            refr,
+            mix,
+            absorption,
+            medium: None,
+        }
+    }
+
+    // smoky glass, scatters light inside the volume
+    #[allow(unused)]
+    pub fn with_medium(mut self, medium: Medium) -> Self {
+        self.medium = Some(medium);
+        self
+    }
+
+    // Light travelling inside the object is attenuated by
+    // exp(-absorption * distance) per channel.
+    pub fn with_absorption(mut self, absorption: DynValue<Color>) -> Self {
+        self.absorption = absorption;
+        self
     }
+
+    // attenuation of the ray travelled inside the object to the hit
+    fn attenuation(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        let absorption = self.absorption.get(s, i);
+        let d = dist(i.ray.orig, i.hit.pos);
+        (absorption * -d).map(f32::exp)
+    }
+}
+
+// absorption coefficient that tints white light to `color` after
+// travelling `distance` in the volume
+pub fn absorption_for(color: Color, distance: f32) -> Color {
+    color.map(|c| -c.max(1e-6).ln() / distance)
 }
 
+#[allow(unused)]
 pub fn transparent(reflectivity: f32, ior: f32) -> ShaderType {
     Transparency::new(reflectivity, ior.into()).into()
 }
@@ -0,0 +1,43 @@
+use crate::common::{dist, Color};
+use crate::medium::Medium;
+use crate::scene::Scene;
+use crate::shader::{Incidence, Shader};
+
+const BIAS: f32 = 1e-4;
+
+// Fills a closed object with a participating medium, e.g. smoke. The surface
+// itself is invisible, use `Transparency::with_medium` for smoky glass.
+pub struct Volume {
+    pub medium: Medium,
+}
+
+impl Volume {
+    pub fn new(medium: Medium) -> Self {
+        Self { medium }
+    }
+}
+
+impl Shader for Volume {
+    fn render(&self, s: &Scene, i: &Incidence<'_, '_, '_>) -> Option<Color> {
+        // pass through the boundary, continuing in the same direction
+        let orig = i.hit.pos + i.ray.dir * BIAS;
+        let ray = i.ray.scattered(orig, i.ray.dir);
+        let behind = s.trace_ray(&ray, i.depth + 1)?;
+
+        if !i.hit.inside {
+            return Some(behind);
+        }
+
+        // the ray travelled inside the volume from its origin to the hit
+        let len = dist(i.ray.orig, i.hit.pos);
+        Some(self.medium.integrate(s, i.ray, len, behind))
+    }
+
+    fn transmittance(&self, _s: &Scene, i: &Incidence<'_, '_, '_>) -> Color {
+        if i.hit.inside {
+            self.medium.transmittance(i.ray.orig, i.hit.pos)
+        } else {
+            Color::White
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Adaptive sampling, adds samples to a pixel until the estimated error of
+// its mean falls below a threshold. Enabled with
+// `.adaptive(Some(Adaptive::new(4, 64, 0.02)))` on the render config.
+use crate::common::Color;
+
+#[derive(Debug, Clone)]
+pub struct Adaptive {
+    pub min_samples: usize,
+    pub max_samples: usize,
+    // samples added between two error estimates
+    pub batch: usize,
+    // tolerated standard error of the mean, relative to the pixel
+    // brightness
+    pub threshold: f32,
+}
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self {
+            min_samples: 4,
+            max_samples: 64,
+            batch: 4,
+            threshold: 0.02,
+        }
+    }
+}
+
+#[allow(unused)]
+impl Adaptive {
+    pub fn new(min_samples: usize, max_samples: usize, threshold: f32) -> Self {
+        Self {
+            min_samples,
+            max_samples,
+            threshold,
+            ..Self::default()
+        }
+    }
+
+    // Draws samples with f(k) for k = 0, 1, .. until the pixel converges.
+    // f returns the position of the sample and its color.
+    pub fn sample_pixel<F>(&self, mut f: F) -> Vec<([f32; 2], Color)>
+    where
+        F: FnMut(usize) -> ([f32; 2], Color),
+    {
+        let max = self.max_samples.max(1);
+        let mut samples = Vec::with_capacity(max);
+        // running sums of the luminance and its square
+        let (mut sum, mut sum2) = (0.0, 0.0);
+
+        while samples.len() < max {
+            let n = if samples.is_empty() {
+                self.min_samples.max(2)
+            } else {
+                self.batch.max(1)
+            };
+
+            for _ in 0..n.min(max - samples.len()) {
+                let (p, c) = f(samples.len());
+                let l = luminance(c);
+                sum += l;
+                sum2 += l * l;
+                samples.push((p, c));
+            }
+
+            if self.converged(samples.len(), sum, sum2) {
+                break;
+            }
+        }
+
+        samples
+    }
+
+    // whether n samples whose luminances sum to sum, and their squares to
+    // sum2, are enough for the pixel
+    pub fn converged(&self, n: usize, sum: f32, sum2: f32) -> bool {
+        if n >= self.max_samples.max(1) {
+            return true;
+        }
+        if n < self.min_samples.max(2) {
+            return false;
+        }
+
+        let n = n as f32;
+        let mean = sum / n;
+        let var = ((sum2 - sum * sum / n) / (n - 1.0)).max(0.0);
+        let error = (var / n).sqrt();
+        // the tolerance is larger in bright areas, like the eye
+        error <= self.threshold * (mean + 0.1)
+    }
+}
+
+pub fn luminance(c: Color) -> f32 {
+    0.2126 * c.r() + 0.7152 * c.g() + 0.0722 * c.b()
+}
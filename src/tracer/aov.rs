@@ -0,0 +1,110 @@
+// Arbitrary output variables, passes rendered alongside the beauty image
+// for compositing, filled from the primary hit of the pixel
+use crate::common::{dist, Color, Ray};
+use crate::scene::Scene;
+use crate::shader::Incidence;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aov {
+    // distance along the ray from the view plane to the hit, infinite on
+    // misses
+    Depth,
+    // world space normal of the hit
+    Normal,
+    // base color of the shader, white for shaders without one
+    Albedo,
+    // index of the object hit plus one, 0 for the background
+    ObjectId,
+}
+
+impl Aov {
+    pub fn name(self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::ObjectId => "id",
+        }
+    }
+
+    // channels of the pass in a multi-layer EXR, with the components of the
+    // color they are taken from
+    pub fn channels(self) -> Vec<(String, usize)> {
+        match self {
+            Aov::Depth => vec![("Z".to_string(), 0)],
+            Aov::ObjectId => vec![("id".to_string(), 0)],
+            Aov::Normal | Aov::Albedo => {
+                let names = if self == Aov::Normal {
+                    ["X", "Y", "Z"]
+                } else {
+                    ["R", "G", "B"]
+                };
+                (0..3)
+                    .map(|c| (format!("{}.{}", self.name(), names[c]), c))
+                    .collect()
+            }
+        }
+    }
+
+    // maps the raw values into displayable colors for 8-bit images
+    pub fn display(self, values: &[Color]) -> Vec<Color> {
+        match self {
+            Aov::Depth => {
+                let far = values
+                    .iter()
+                    .map(|c| c.r())
+                    .filter(|d| d.is_finite())
+                    .fold(0.0, f32::max)
+                    .max(1e-6);
+                values
+                    .iter()
+                    .map(|c| Color::from_intensity(c.r().min(far) / far))
+                    .collect()
+            }
+            Aov::Normal => values.iter().map(|n| (*n + 1.0) * 0.5).collect(),
+            Aov::Albedo => values.iter().map(|c| c.to_srgb()).collect(),
+            Aov::ObjectId => values.iter().map(|c| id_color(c.r())).collect(),
+        }
+    }
+}
+
+// distinct color per object, spreading the hues with the golden ratio
+fn id_color(id: f32) -> Color {
+    if id <= 0.0 {
+        return Color::Black;
+    }
+    let frac = |x: f32| x - x.floor();
+    Color([frac(id * 0.618_034), frac(id * 0.381_966 + 0.3), frac(id * 0.2)])
+}
+
+// values of the passes for the primary ray of a pixel
+pub fn aov_sample(s: &Scene, ray: &Ray, aovs: &[Aov]) -> Vec<Color> {
+    let hit = s.nearest_hit_id(ray);
+    aovs.iter()
+        .map(|aov| match &hit {
+            None => match aov {
+                Aov::Depth => Color::from_intensity(std::f32::INFINITY),
+                _ => Color::Black,
+            },
+            Some((id, hit)) => {
+                let obj = s.objs[*id].as_ref();
+                let i = Incidence {
+                    ray,
+                    obj,
+                    hit,
+                    trans: None,
+                    depth: 0,
+                };
+                match aov {
+                    Aov::Depth => {
+                        Color::from_intensity(dist(hit.pos, ray.orig))
+                    }
+                    Aov::Normal => Color(hit.norm.0),
+                    Aov::Albedo => obj.albedo(s, &i).unwrap_or(Color::White),
+                    Aov::ObjectId => Color::from_intensity(*id as f32 + 1.0),
+                }
+            }
+        })
+        .collect()
+}
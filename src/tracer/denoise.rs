@@ -0,0 +1,112 @@
+// Joint bilateral denoiser guided by the albedo and normal passes.
+//
+// Neighbouring pixels are averaged when they look alike in the guides,
+// which are noise free, so edges and textures survive the smoothing. The
+// beauty is divided by the albedo before filtering to keep texture detail.
+// Enabled with `.denoise(Some(Denoiser::default()))` on the render config.
+//
+// See: https://people.csail.mit.edu/sparis/bf_course/
+use super::{Aov, Film};
+use crate::common::Color;
+
+use rayon::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct Denoiser {
+    // half width of the filter window, in pixels
+    pub radius: u32,
+    pub sigma_spatial: f32,
+    // tolerance on the tone mapped irradiance
+    pub sigma_color: f32,
+    pub sigma_albedo: f32,
+    pub sigma_normal: f32,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Self {
+            radius: 4,
+            sigma_spatial: 2.5,
+            sigma_color: 0.2,
+            sigma_albedo: 0.1,
+            sigma_normal: 0.3,
+        }
+    }
+}
+
+impl Denoiser {
+    // passes needed to guide the filter
+    pub const GUIDES: [Aov; 2] = [Aov::Albedo, Aov::Normal];
+
+    // filters the beauty of the film, the guides must have been rendered
+    pub fn apply(&self, film: &mut Film) {
+        let guide = |aov: Aov| {
+            film.aovs
+                .iter()
+                .find(|(a, _)| *a == aov)
+                .map(|(_, values)| values.clone())
+        };
+        let (albedo, normal) = match (guide(Aov::Albedo), guide(Aov::Normal)) {
+            (Some(albedo), Some(normal)) => (albedo, normal),
+            _ => return,
+        };
+
+        let (w, h) = (film.w as i64, film.h as i64);
+        let irradiance = film
+            .pixels()
+            .iter()
+            .zip(albedo.iter())
+            .map(|(c, a)| demodulate(*c, *a))
+            .collect::<Vec<_>>();
+        let r = self.radius as i64;
+        let weight = |d2: f32, sigma: f32| (-d2 / (2.0 * sigma * sigma)).exp();
+        let dist2 = |a: Color, b: Color| {
+            let d = a - b;
+            d.r() * d.r() + d.g() * d.g() + d.b() * d.b()
+        };
+        // compresses bright values so fireflies do not dominate
+        let tone = |c: Color| c.map(|x| x.max(0.0) / (1.0 + x.max(0.0)));
+
+        let filtered = (0..w * h)
+            .into_par_iter()
+            .map(|k| {
+                let (x, y) = (k % w, k / w);
+                let p = k as usize;
+                let (c0, a0, n0) = (tone(irradiance[p]), albedo[p], normal[p]);
+                let mut sum = Color::Black;
+                let mut total = 0.0;
+
+                for qy in (y - r).max(0)..(y + r + 1).min(h) {
+                    for qx in (x - r).max(0)..(x + r + 1).min(w) {
+                        let q = (qy * w + qx) as usize;
+                        let d2 = ((qx - x).pow(2) + (qy - y).pow(2)) as f32;
+                        let c = irradiance[q];
+                        let dc = dist2(tone(c), c0);
+                        let wq = weight(d2, self.sigma_spatial)
+                            * weight(dc, self.sigma_color)
+                            * weight(dist2(albedo[q], a0), self.sigma_albedo)
+                            * weight(dist2(normal[q], n0), self.sigma_normal);
+                        sum = sum + c * wq;
+                        total += wq;
+                    }
+                }
+
+                remodulate(sum * (1.0 / total), a0)
+            })
+            .collect::<Vec<_>>();
+
+        for (k, c) in filtered.into_iter().enumerate() {
+            let (x, y) = ((k as i64 % w) as u32, (k as i64 / w) as u32);
+            film.set(x, y, c);
+        }
+    }
+}
+
+// beauty divided by the albedo, black albedo keeps the beauty as is
+fn demodulate(c: Color, albedo: Color) -> Color {
+    c.mix_with(albedo, |c, a| if a > 1e-3 { c / a } else { c })
+}
+
+fn remodulate(c: Color, albedo: Color) -> Color {
+    c.mix_with(albedo, |c, a| if a > 1e-3 { c * a } else { c })
+}
@@ -0,0 +1,79 @@
+// Display transform turning the linear radiance of the film into an 8-bit
+// image: exposure, then tone mapping, then the output transfer function.
+use crate::common::{Color, ColorSpace};
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    // values above 1.0 clip to white
+    Clamp,
+    // x / (1 + x), never reaches white
+    Reinhard,
+    // John Hable's Uncharted 2 curve
+    // See: http://filmicworlds.com/blog/filmic-tonemapping-operators/
+    Filmic,
+    // Krzysztof Narkowicz's fit of the ACES reference rendering transform
+    // See: https://knarkowicz.wordpress.com/2016/01/06/aces-filmic-tone-mapping-curve/
+    Aces,
+}
+
+#[derive(Debug, Clone)]
+pub struct DisplayTransform {
+    // in stops, each one doubles the brightness
+    pub exposure: f32,
+    pub tone_map: ToneMap,
+    // encoding of the output, the transfer function is applied once here
+    pub output: ColorSpace,
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            tone_map: ToneMap::Clamp,
+            output: ColorSpace::Srgb,
+        }
+    }
+}
+
+#[allow(unused)]
+impl DisplayTransform {
+    pub fn new(exposure: f32, tone_map: ToneMap) -> Self {
+        Self {
+            exposure,
+            tone_map,
+            output: ColorSpace::Srgb,
+        }
+    }
+
+    // linear radiance to display encoded values in [0, 1]
+    pub fn apply(&self, c: Color) -> Color {
+        let c = c * 2f32.powf(self.exposure);
+        let c = c.map(|x| self.tone_map.map(x.max(0.0))).regularize();
+        self.output.encode(c)
+    }
+}
+
+impl ToneMap {
+    pub fn map(self, x: f32) -> f32 {
+        match self {
+            ToneMap::Clamp => x,
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::Filmic => {
+                // linear white point
+                const W: f32 = 11.2;
+                hable(2.0 * x) / hable(W)
+            }
+            ToneMap::Aces => {
+                let x = x * 0.6;
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                (x * (a * x + b)) / (x * (c * x + d) + e)
+            }
+        }
+    }
+}
+
+fn hable(x: f32) -> f32 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
@@ -0,0 +1,360 @@
+// Floating point film holding the linear radiance of the rendered image
+use super::image::{Rgb, RgbImage};
+use super::{Aov, DisplayTransform, Filter, Tile, ToneMap};
+use crate::common::{Color, ColorSpace};
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct Film {
+    pub w: u32,
+    pub h: u32,
+    // weighted sums of the samples and the sums of their weights
+    pixels: Vec<Color>,
+    weights: Vec<f32>,
+    // applied when saving to 8-bit formats
+    pub display: DisplayTransform,
+    // additional passes with their raw values
+    pub aovs: Vec<(Aov, Vec<Color>)>,
+}
+
+#[allow(unused)]
+impl Film {
+    pub fn new(w: u32, h: u32) -> Self {
+        Self {
+            w,
+            h,
+            pixels: vec![Color::Black; (w * h) as usize],
+            weights: vec![0.0; (w * h) as usize],
+            display: DisplayTransform::default(),
+            aovs: Vec::new(),
+        }
+    }
+
+    pub fn with_aovs(mut self, aovs: &[Aov]) -> Self {
+        let n = self.pixels.len();
+        self.aovs = aovs
+            .iter()
+            .map(|aov| (*aov, vec![Color::Black; n]))
+            .collect();
+        self
+    }
+
+    // sets the values of all passes, in the order they were requested
+    pub fn set_aovs(&mut self, x: u32, y: u32, values: &[Color]) {
+        let k = (y * self.w + x) as usize;
+        for ((_, pass), v) in self.aovs.iter_mut().zip(values.iter()) {
+            pass[k] = *v;
+        }
+    }
+
+    // film holding a single pass, for 8-bit formats the values are mapped
+    // to displayable colors
+    pub fn aov_film(&self, aov: Aov, ldr: bool) -> Option<Film> {
+        let (_, values) = self.aovs.iter().find(|(a, _)| *a == aov)?;
+        let pixels = if ldr {
+            aov.display(values)
+        } else {
+            values.clone()
+        };
+        Some(Film {
+            w: self.w,
+            h: self.h,
+            weights: vec![1.0; pixels.len()],
+            pixels,
+            display: DisplayTransform {
+                exposure: 0.0,
+                tone_map: ToneMap::Clamp,
+                output: ColorSpace::Linear,
+            },
+            aovs: Vec::new(),
+        })
+    }
+
+    // part of the film within the tile, with its passes
+    pub fn crop(&self, tile: &Tile) -> Film {
+        let index = |x: u32, y: u32| (y * self.w + x) as usize;
+        let pick = |values: &[Color]| {
+            tile.pixels().map(|(x, y)| values[index(x, y)]).collect()
+        };
+        let weights = tile
+            .pixels()
+            .map(|(x, y)| self.weights[index(x, y)])
+            .collect();
+        Film {
+            w: tile.w,
+            h: tile.h,
+            pixels: pick(&self.pixels),
+            weights,
+            display: self.display.clone(),
+            aovs: self
+                .aovs
+                .iter()
+                .map(|(aov, values)| (*aov, pick(values)))
+                .collect(),
+        }
+    }
+
+    // Negative lobes of the filters and spectral samples can give negative
+    // values, they are clamped to black.
+    pub fn get(&self, x: u32, y: u32) -> Color {
+        let k = (y * self.w + x) as usize;
+        if self.weights[k] <= 0.0 {
+            return Color::Black;
+        }
+        (self.pixels[k] * (1.0 / self.weights[k])).map(|c| c.max(0.0))
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: Color) {
+        let k = (y * self.w + x) as usize;
+        self.pixels[k] = color;
+        self.weights[k] = 1.0;
+    }
+
+    pub fn pixels(&self) -> Vec<Color> {
+        (0..self.h)
+            .flat_map(|y| (0..self.w).map(move |x| (x, y)))
+            .map(|(x, y)| self.get(x, y))
+            .collect()
+    }
+
+    // Adds a sample at continuous pixel coordinates to the pixels within
+    // the radius of the filter, pixel centers are at half-integers.
+    pub fn add_sample(
+        &mut self,
+        x: f32,
+        y: f32,
+        color: Color,
+        filter: &Filter,
+    ) {
+        // pixel centers in (x - r, x + r], so a sample on a pixel edge only
+        // reaches one pixel with the default box filter, like `x as u32`
+        let r = filter.radius();
+        let x0 = ((x - 0.5 - r).floor() + 1.0).max(0.0) as u32;
+        let y0 = ((y - 0.5 - r).floor() + 1.0).max(0.0) as u32;
+        let x1 = ((x - 0.5 + r).floor() as i64).min(self.w as i64 - 1);
+        let y1 = ((y - 0.5 + r).floor() as i64).min(self.h as i64 - 1);
+
+        for py in y0 as i64..=y1 {
+            for px in x0 as i64..=x1 {
+                let dx = px as f32 + 0.5 - x;
+                let dy = py as f32 + 0.5 - y;
+                let weight = filter.eval(dx, dy);
+                if weight == 0.0 {
+                    continue;
+                }
+                let k = (py as u32 * self.w + px as u32) as usize;
+                self.pixels[k] = self.pixels[k] + color * weight;
+                self.weights[k] += weight;
+            }
+        }
+    }
+
+    // 8-bit image through the display transform
+    pub fn to_rgb_image(&self) -> RgbImage {
+        RgbImage::from_fn(self.w, self.h, |x, y| {
+            Rgb(self.display.apply(self.get(x, y)).into())
+        })
+    }
+
+    // Saves the film in the format given by the extension of the path:
+    // exr, hdr and pfm keep the linear HDR data, anything else is written
+    // by the image crate. The passes are stored as layers in EXR files, or
+    // next to the image as "name.pass.ext" for other formats.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+        let ext = ext.as_ref().map(String::as_str);
+
+        match ext {
+            Some("exr") => return self.save_exr(path),
+            Some("hdr") => self.save_hdr(path)?,
+            Some("pfm") => self.save_pfm(path)?,
+            _ => self.to_rgb_image().save(path)?,
+        }
+
+        let ldr = ext != Some("hdr") && ext != Some("pfm");
+        for (aov, _) in self.aovs.iter() {
+            if let Some(film) = self.aov_film(*aov, ldr) {
+                film.save(aov_path(path, *aov))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save_exr<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let channel = |values: &[Color], c: usize| {
+            values.iter().map(|v| v.0[c]).collect::<Vec<_>>()
+        };
+        let pixels = self.pixels();
+        let mut channels = vec![
+            ("R".to_string(), channel(&pixels, 0)),
+            ("G".to_string(), channel(&pixels, 1)),
+            ("B".to_string(), channel(&pixels, 2)),
+        ];
+        for (aov, values) in self.aovs.iter() {
+            for (name, c) in aov.channels() {
+                channels.push((name, channel(values, c)));
+            }
+        }
+        write_exr(path, self.w, self.h, channels)
+    }
+
+    // Radiance RGBE, with flat (uncompressed) scanlines
+    pub fn save_hdr<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut f = BufWriter::new(File::create(path)?);
+        write!(f, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n")?;
+        write!(f, "-Y {} +X {}\n", self.h, self.w)?;
+        for c in self.pixels() {
+            f.write_all(&rgbe(c))?;
+        }
+        f.flush()
+    }
+
+    // Portable float map, scanlines go from bottom to top
+    pub fn save_pfm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut f = BufWriter::new(File::create(path)?);
+        // negative scale means little endian
+        write!(f, "PF\n{} {}\n-1.0\n", self.w, self.h)?;
+        for y in (0..self.h).rev() {
+            for x in 0..self.w {
+                for v in self.get(x, y).0.iter() {
+                    f.write_all(&v.to_le_bytes())?;
+                }
+            }
+        }
+        f.flush()
+    }
+}
+
+// "image.png" to "image.depth.png"
+fn aov_path(path: &Path, aov: Aov) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let mut name = format!("{}.{}", stem, aov.name());
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        name = format!("{}.{}", name, ext);
+    }
+    path.with_file_name(name)
+}
+
+// Shared exponent encoding of Radiance files. NaN is stored as black,
+// values too large for the exponent saturate.
+fn rgbe(c: Color) -> [u8; 4] {
+    // max ignores NaN
+    let c = c.map(|v| v.max(0.0).min(f32::MAX));
+    let v = c.r().max(c.g()).max(c.b());
+    if v < 1e-32 {
+        return [0; 4];
+    }
+
+    // v = m * 2^e with m in [0.5, 1)
+    let e = (v.log2().floor() as i32 + 1).min(127);
+    let scale = 256.0 / 2f32.powi(e);
+    let m = |k: f32| (k * scale).min(255.0) as u8;
+    [m(c.r()), m(c.g()), m(c.b()), (e + 128) as u8]
+}
+
+// Writes 32-bit float channels to an uncompressed single part scanline
+// OpenEXR file. Channels are named like "R" or "albedo.R" for layers.
+//
+// See: https://www.openexr.com/documentation/openexrfilelayout.pdf
+pub fn write_exr<P: AsRef<Path>>(
+    path: P,
+    w: u32,
+    h: u32,
+    mut channels: Vec<(String, Vec<f32>)>,
+) -> io::Result<()> {
+    // channels are stored in alphabetical order
+    channels.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut header: Vec<u8> = Vec::new();
+    header.extend_from_slice(&[0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0]);
+
+    let mut attr = |name: &str, ty: &str, value: &[u8]| {
+        header.extend_from_slice(name.as_bytes());
+        header.push(0);
+        header.extend_from_slice(ty.as_bytes());
+        header.push(0);
+        header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+        header.extend_from_slice(value);
+    };
+
+    let mut chlist = Vec::new();
+    for (name, _) in channels.iter() {
+        chlist.extend_from_slice(name.as_bytes());
+        chlist.push(0);
+        // pixel type FLOAT, pLinear and reserved, x and y sampling
+        chlist.extend_from_slice(&2i32.to_le_bytes());
+        chlist.extend_from_slice(&[0; 4]);
+        chlist.extend_from_slice(&1i32.to_le_bytes());
+        chlist.extend_from_slice(&1i32.to_le_bytes());
+    }
+    chlist.push(0);
+
+    let mut window = Vec::new();
+    for v in [0, 0, w as i32 - 1, h as i32 - 1].iter() {
+        window.extend_from_slice(&v.to_le_bytes());
+    }
+
+    attr("channels", "chlist", &chlist);
+    attr("compression", "compression", &[0]);
+    attr("dataWindow", "box2i", &window);
+    attr("displayWindow", "box2i", &window);
+    attr("lineOrder", "lineOrder", &[0]);
+    attr("pixelAspectRatio", "float", &1f32.to_le_bytes());
+    attr("screenWindowCenter", "v2f", &[0; 8]);
+    attr("screenWindowWidth", "float", &1f32.to_le_bytes());
+    header.push(0);
+
+    let mut f = BufWriter::new(File::create(path)?);
+    f.write_all(&header)?;
+
+    // offset table, one scanline per block
+    let line_size = (w as usize * 4 * channels.len()) as u64;
+    let start = header.len() as u64 + 8 * h as u64;
+    for y in 0..h as u64 {
+        f.write_all(&(start + y * (8 + line_size)).to_le_bytes())?;
+    }
+
+    for y in 0..h as usize {
+        f.write_all(&(y as i32).to_le_bytes())?;
+        f.write_all(&(line_size as i32).to_le_bytes())?;
+        for (_, data) in channels.iter() {
+            let w = w as usize;
+            for v in data[y * w..(y + 1) * w].iter() {
+                f.write_all(&v.to_le_bytes())?;
+            }
+        }
+    }
+    f.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgbe_encoding() {
+        assert_eq!(rgbe(Color::Black), [0, 0, 0, 0]);
+        assert_eq!(rgbe(Color::White), [128, 128, 128, 129]);
+        assert_eq!(rgbe(Color::from_intensity(1e30)), [201, 201, 201, 228]);
+
+        let inf = Color::from_intensity(std::f32::INFINITY);
+        assert_eq!(rgbe(inf), [255, 255, 255, 255]);
+        let nan = Color([std::f32::NAN, 1.0, 1.0]);
+        assert_eq!(rgbe(nan), [0, 128, 128, 129]);
+    }
+
+    #[test]
+    fn box_filter_edge_sample() {
+        let mut film = Film::new(4, 4);
+        film.add_sample(2.0, 1.0, Color::White, &Filter::default());
+        let hit = |k: usize| film.weights[k] > 0.0;
+        assert_eq!((0..16).filter(|&k| hit(k)).collect::<Vec<_>>(), [6]);
+    }
+}
@@ -0,0 +1,103 @@
+// Reconstruction filters weighting the samples splatted into the pixels
+// around them. All filters are separable, radius is in pixels.
+//
+// See: https://www.pbr-book.org/3ed-2018/Sampling_and_Reconstruction/Image_Reconstruction
+use std::f32::consts::PI;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub enum Filter {
+    Box { radius: f32 },
+    Tent { radius: f32 },
+    // alpha controls the falloff, larger values are sharper
+    Gaussian { radius: f32, alpha: f32 },
+    // Mitchell-Netravali cubic, b = c = 1/3 is the recommended balance
+    // between blurring and ringing
+    Mitchell { radius: f32, b: f32, c: f32 },
+    // sinc windowed by a sinc stretched by tau, usually the radius
+    Lanczos { radius: f32, tau: f32 },
+}
+
+impl Default for Filter {
+    // each sample only counts for its own pixel
+    fn default() -> Self {
+        Filter::Box { radius: 0.5 }
+    }
+}
+
+#[allow(unused)]
+impl Filter {
+    pub fn gaussian(radius: f32) -> Self {
+        Filter::Gaussian { radius, alpha: 2.0 }
+    }
+
+    pub fn mitchell(radius: f32) -> Self {
+        let third = 1.0 / 3.0;
+        Filter::Mitchell {
+            radius,
+            b: third,
+            c: third,
+        }
+    }
+
+    pub fn lanczos(radius: f32) -> Self {
+        Filter::Lanczos { radius, tau: radius }
+    }
+
+    pub fn radius(&self) -> f32 {
+        match *self {
+            Filter::Box { radius }
+            | Filter::Tent { radius }
+            | Filter::Gaussian { radius, .. }
+            | Filter::Mitchell { radius, .. }
+            | Filter::Lanczos { radius, .. } => radius,
+        }
+    }
+
+    // weight of a sample at offset (dx, dy) from the pixel center
+    pub fn eval(&self, dx: f32, dy: f32) -> f32 {
+        self.eval_1d(dx) * self.eval_1d(dy)
+    }
+
+    fn eval_1d(&self, x: f32) -> f32 {
+        let x = x.abs();
+        let r = self.radius();
+        if x > r {
+            return 0.0;
+        }
+
+        match *self {
+            Filter::Box { .. } => 1.0,
+            Filter::Tent { .. } => r - x,
+            Filter::Gaussian { alpha, .. } => {
+                (-alpha * x * x).exp() - (-alpha * r * r).exp()
+            }
+            Filter::Mitchell { b, c, .. } => mitchell_1d(2.0 * x / r, b, c),
+            Filter::Lanczos { tau, .. } => sinc(x) * sinc(x / tau),
+        }
+    }
+}
+
+// x in [0, 2)
+fn mitchell_1d(x: f32, b: f32, c: f32) -> f32 {
+    let (x2, x3) = (x * x, x * x * x);
+    let k = if x < 1.0 {
+        (12.0 - 9.0 * b - 6.0 * c) * x3
+            + (-18.0 + 12.0 * b + 6.0 * c) * x2
+            + (6.0 - 2.0 * b)
+    } else {
+        (-b - 6.0 * c) * x3
+            + (6.0 * b + 30.0 * c) * x2
+            + (-12.0 * b - 48.0 * c) * x
+            + (8.0 * b + 24.0 * c)
+    };
+    k / 6.0
+}
+
+fn sinc(x: f32) -> f32 {
+    if x < 1e-5 {
+        return 1.0;
+    }
+    let x = PI * x;
+    x.sin() / x
+}
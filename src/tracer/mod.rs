@@ -1,8 +1,27 @@
-use super::image::{ImageBuffer, Rgb, RgbImage};
+use super::image;
 use crate::common::*;
 use crate::scene::Scene;
 
+pub mod adaptive;
+pub mod aov;
+pub mod denoise;
+pub mod display;
+pub mod film;
+pub mod filter;
 pub mod modular;
+pub mod progressive;
+pub mod sampler;
+pub mod tile;
+
+pub use self::adaptive::Adaptive;
+pub use self::aov::Aov;
+pub use self::denoise::Denoiser;
+pub use self::display::{DisplayTransform, ToneMap};
+pub use self::film::Film;
+pub use self::filter::Filter;
+pub use self::progressive::Progressive;
+pub use self::sampler::Sampler;
+pub use self::tile::{Tile, Tiles};
 
 #[derive(Debug, Clone, Builder)]
 pub struct RenderConfig {
@@ -12,30 +31,99 @@ pub struct RenderConfig {
     pub w: u32,
     #[builder(default = "1200")]
     pub h: u32,
+    // trace a sampled wavelength per ray and convert to RGB with the CIE
+    // color matching functions
+    #[builder(default = "false")]
+    pub spectral: bool,
+    // sample each pixel until it converges, the antialiasing pattern then
+    // only gives the positions of the samples
+    #[builder(default)]
+    pub adaptive: Option<Adaptive>,
+    // reconstruction filter spreading the samples over the pixels
+    #[builder(default)]
+    pub filter: Filter,
+    // exposure, tone mapping and encoding of the saved 8-bit images
+    #[builder(default)]
+    pub display: DisplayTransform,
+    // passes saved along with the image
+    #[builder(default)]
+    pub aovs: Vec<Aov>,
+    // filters the noise of the image after tracing, renders the albedo and
+    // normal passes to guide it
+    #[builder(default)]
+    pub denoise: Option<Denoiser>,
+    // render in passes of one sample per pixel, with snapshots of the image
+    // in between. The antialiasing pattern gives the sample of each pass.
+    #[builder(default)]
+    pub progressive: Option<Progressive>,
+    // size and order of the tiles the image is rendered by
+    #[builder(default)]
+    pub tiles: Tiles,
+    // only render the pixels within, to render a crop of the image or split
+    // it between machines
+    #[builder(default)]
+    pub region: Option<Tile>,
 }
 
 #[derive(Debug, Clone)]
-pub struct AAPattern(Vec<[f32; 2]>);
+pub struct AAPattern(Sampler);
 
+#[allow(unused)]
 impl AAPattern {
     // See: https://en.wikipedia.org/wiki/Supersampling
     pub fn ssaa4x() -> Self {
-        AAPattern(
+        AAPattern(Sampler::Pattern(
             [[0.25, 0.25], [0.25, 0.75], [0.75, 0.25], [0.75, 0.75]].to_vec(),
-        )
+        ))
     }
-    #[allow(unused)]
     pub fn hraa() -> Self {
-        AAPattern(
+        AAPattern(Sampler::Pattern(
             [[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0], [0.5, 0.5]]
                 .to_vec(),
-        )
+        ))
+    }
+
+    pub fn random(count: usize) -> Self {
+        AAPattern(Sampler::Random(count))
+    }
+    // n x n jittered samples
+    pub fn stratified(n: usize) -> Self {
+        AAPattern(Sampler::Stratified(n))
+    }
+    pub fn halton(count: usize) -> Self {
+        AAPattern(Sampler::Halton(count))
+    }
+    pub fn sobol(count: usize) -> Self {
+        AAPattern(Sampler::Sobol(count))
+    }
+    pub fn blue_noise(count: usize) -> Self {
+        AAPattern(Sampler::blue_noise(count))
+    }
+
+    pub fn sampler(&self) -> &Sampler {
+        &self.0
+    }
+
+    // k-th sample of the pixel, jittered randomly without antialiasing
+    pub fn pixel_offset(
+        aa: &Option<Self>,
+        x: u32,
+        y: u32,
+        k: usize,
+    ) -> [f32; 2] {
+        let [dx, dy] = match aa {
+            None if k == 0 => [0.5, 0.5],
+            None => [rand01(), rand01()],
+            Some(aa) => aa.0.sample(x, y, k),
+        };
+        [x as f32 + dx, y as f32 + dy]
     }
 
     pub fn pixel_offsets(aa: &Option<Self>, x: u32, y: u32) -> Vec<[f32; 2]> {
         match aa {
-            None => vec![[x as f32, y as f32]],
+            None => vec![[x as f32 + 0.5, y as f32 + 0.5]],
             Some(aa) => aa.0
+                          .samples(x, y)
                           .iter()
                           .map(|[dx, dy]| [x as f32 + dx, y as f32 + dy])
                           .collect(),
@@ -1,43 +1,326 @@
-use super::{AAPattern, RenderConfig};
-use super::{Color, ImageBuffer, Rgb, RgbImage, Scene};
+use super::adaptive::luminance;
+use super::aov::{aov_sample, Aov};
+use super::sampler::progressive_1d;
+use super::{AAPattern, Denoiser, Film, Progressive, RenderConfig};
+use super::{Color, Scene, Tile};
+use crate::common::{rand01, sample_wavelength, spectral_to_rgb};
 
 use pbr;
-use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[allow(dead_code)]
-pub fn trace(s: Scene, conf: RenderConfig) -> RgbImage {
+pub fn trace(s: Scene, conf: RenderConfig) -> Film {
+  trace_with(s, conf, |_, _| {}, |_, _| true)
+}
+
+// In progressive mode, on_snapshot is given the film and the number of
+// passes done at each snapshot, the render stops when it returns false.
+// Snapshots are neither denoised nor reduced to the requested passes.
+#[allow(dead_code)]
+pub fn trace_progressive<F>(
+  s: Scene,
+  conf: RenderConfig,
+  on_snapshot: F,
+) -> Film
+where
+  F: FnMut(&Film, usize) -> bool,
+{
+  trace_with(s, conf, |_, _| {}, on_snapshot)
+}
+
+// on_tile is given each tile with the film once its samples are added, in
+// the order they complete and in every pass in progressive mode. Samples of
+// the neighbouring tiles still to come may add to the pixels on its edges
+// with wide filters.
+#[allow(dead_code)]
+pub fn trace_tiles<T>(s: Scene, conf: RenderConfig, on_tile: T) -> Film
+where
+  T: FnMut(&Tile, &Film),
+{
+  trace_with(s, conf, on_tile, |_, _| true)
+}
+
+fn trace_with<T, F>(
+  s: Scene,
+  conf: RenderConfig,
+  mut on_tile: T,
+  mut on_snapshot: F,
+) -> Film
+where
+  T: FnMut(&Tile, &Film),
+  F: FnMut(&Film, usize) -> bool,
+{
   let (w, h) = (conf.w, conf.h);
-  let mut pb = pbr::ProgressBar::new((w * h) as u64);
-  pb.set_max_refresh_rate(Some(std::time::Duration::from_millis(100)));
-
-  let mut film = ImageBuffer::new(w, h);
-
-  let coords = film
-    .enumerate_pixels()
-    .map(|(x, y, _)| (x, y))
-    .collect::<Vec<_>>();
-
-  coords
-    .into_par_iter()
-    .map(move |(x, y)| {
-      let colors = AAPattern::pixel_offsets(&conf.aa, x, y)
-        .into_iter()
-        .map(|[x, y]| {
-          let ray = s.generate_ray(x, y, w as f32, h as f32);
-          s.trace_ray(&ray, 0).unwrap_or(Color::Green).regularize()
-        })
-        .collect::<Vec<_>>();
-
-      let color = Color::average(&colors);
-      pb.inc();
-      (x, y, color)
-    })
-    .for_each(|(x, y, color)| {
-      let pixel = Rgb(color.into());
-      film.put_pixel(x, y, pixel)
-    });
+  let mut aovs = conf.aovs.clone();
+  if conf.denoise.is_some() {
+    for guide in Denoiser::GUIDES.iter() {
+      if !aovs.contains(guide) {
+        aovs.push(*guide);
+      }
+    }
+  }
+  let mut film = Film::new(w, h).with_aovs(&aovs);
+  film.display = conf.display.clone();
 
-  pb.finish();
+  // pixels outside the region are left black, all of them if the region
+  // is empty or outside the image
+  let image = Tile::new(0, 0, w, h);
+  let tiles = match conf.region {
+    Some(region) => match region.intersect(&image) {
+      Some(region) => conf.tiles.split(&region),
+      None => Vec::new(),
+    },
+    None => conf.tiles.split(&image),
+  };
+
+  match &conf.progressive {
+    None => render(&s, &conf, &mut film, &tiles, &aovs, &mut on_tile),
+    Some(progressive) => render_progressive(
+      &s,
+      &conf,
+      progressive,
+      &mut film,
+      &tiles,
+      &aovs,
+      &mut on_tile,
+      &mut on_snapshot,
+    ),
+  }
+
+  if let Some(denoiser) = &conf.denoise {
+    denoiser.apply(&mut film);
+    // only keep the passes requested
+    film.aovs.retain(|(aov, _)| conf.aovs.contains(aov));
+  }
 
   film
 }
+
+// all the samples of the pixels at once
+fn render<T>(
+  s: &Scene,
+  conf: &RenderConfig,
+  film: &mut Film,
+  tiles: &[Tile],
+  aovs: &[Aov],
+  on_tile: &mut T,
+) where
+  T: FnMut(&Tile, &Film),
+{
+  let count = tiles.iter().map(|t| (t.w * t.h) as u64).sum();
+  let mut pb = pbr::ProgressBar::new(count);
+  pb.set_max_refresh_rate(Some(Duration::from_millis(100)));
+
+  let pixel = |x, y| {
+    let samples = match &conf.adaptive {
+      None => {
+        let offsets = AAPattern::pixel_offsets(&conf.aa, x, y);
+        let n = offsets.len();
+        offsets
+          .into_iter()
+          .enumerate()
+          .map(|(k, p)| (p, trace_sample(s, conf, p, stratum(k, n))))
+          .collect::<Vec<_>>()
+      }
+      Some(adaptive) => adaptive.sample_pixel(|k| {
+        let p = AAPattern::pixel_offset(&conf.aa, x, y, k);
+        // wavelengths are stratified within each batch
+        let n = adaptive.batch.max(1);
+        (p, trace_sample(s, conf, p, stratum(k % n, n)))
+      }),
+    };
+    Some((samples, pixel_aovs(s, conf, x, y, aovs)))
+  };
+
+  // samples are splatted into the neighbouring pixels as well
+  type Pixel = (Vec<([f32; 2], Color)>, Vec<Color>);
+  let splat = |film: &mut Film, x, y, (samples, aovs): Pixel| {
+    for ([sx, sy], color) in samples {
+      film.add_sample(sx, sy, color, &conf.filter);
+    }
+    film.set_aovs(x, y, &aovs);
+  };
+
+  render_tiles(film, tiles, pixel, splat, |tile, film| {
+    pb.add((tile.w * tile.h) as u64);
+    on_tile(tile, film);
+  });
+  pb.finish();
+}
+
+// one sample per pixel and pass, accumulated in the film. With adaptive
+// sampling, converged pixels are skipped by the following passes.
+#[allow(clippy::too_many_arguments)]
+fn render_progressive<T, F>(
+  s: &Scene,
+  conf: &RenderConfig,
+  progressive: &Progressive,
+  film: &mut Film,
+  tiles: &[Tile],
+  aovs: &[Aov],
+  on_tile: &mut T,
+  on_snapshot: &mut F,
+) where
+  T: FnMut(&Tile, &Film),
+  F: FnMut(&Film, usize) -> bool,
+{
+  let passes = progressive.passes.max(1);
+  let mut pb = pbr::ProgressBar::new(passes as u64);
+  pb.set_max_refresh_rate(Some(Duration::from_millis(100)));
+
+  let index = |x: u32, y: u32| (y * conf.w + x) as usize;
+  // running sums of the luminance of the samples and its square
+  let mut stats = vec![(0.0, 0.0); (conf.w * conf.h) as usize];
+  let mut last_snapshot = Instant::now();
+
+  for pass in 0..passes {
+    let converged = stats
+      .iter()
+      .map(|&(sum, sum2)| match &conf.adaptive {
+        None => false,
+        Some(adaptive) => adaptive.converged(pass, sum, sum2),
+      })
+      .collect::<Vec<_>>();
+    let active = |t: &Tile| t.pixels().any(|(x, y)| !converged[index(x, y)]);
+    if !tiles.iter().any(active) {
+      break;
+    }
+
+    let pixel = |x, y| {
+      if converged[index(x, y)] {
+        return None;
+      }
+      let p = AAPattern::pixel_offset(&conf.aa, x, y, pass);
+      // the wavelengths of any number of passes cover the whole spectrum
+      let u = progressive_1d(x, y, pass);
+      let color = trace_sample(s, conf, p, u);
+      let aovs = if pass == 0 {
+        pixel_aovs(s, conf, x, y, aovs)
+      } else {
+        vec![]
+      };
+      Some((p, color, aovs))
+    };
+
+    type Sample = ([f32; 2], Color, Vec<Color>);
+    let splat = |film: &mut Film, x, y, (p, color, aovs): Sample| {
+      film.add_sample(p[0], p[1], color, &conf.filter);
+      if pass == 0 {
+        film.set_aovs(x, y, &aovs);
+      }
+      let l = luminance(color);
+      let k = index(x, y);
+      stats[k].0 += l;
+      stats[k].1 += l * l;
+    };
+
+    render_tiles(film, tiles, pixel, splat, &mut *on_tile);
+    pb.inc();
+
+    let done = pass + 1;
+    if progressive.snapshot_due(done, last_snapshot.elapsed()) {
+      last_snapshot = Instant::now();
+      // a failed save is reported, the render goes on to the next one
+      if let Some(path) = &progressive.path {
+        if let Err(e) = film.save(path) {
+          eprintln!("\ncould not save the snapshot to {}: {}", path, e);
+        }
+      }
+      if !on_snapshot(film, done) {
+        break;
+      }
+    }
+  }
+
+  pb.finish();
+}
+
+// Renders the pixels of the tiles with pixel, which gives None for the
+// pixels to skip, and adds them to the film with splat. Threads take the
+// tiles in order and each tile is delivered as soon as it is done.
+fn render_tiles<P, R, S, T>(
+  film: &mut Film,
+  tiles: &[Tile],
+  pixel: P,
+  mut splat: S,
+  mut on_tile: T,
+) where
+  P: Fn(u32, u32) -> Option<R> + Sync,
+  R: Send,
+  S: FnMut(&mut Film, u32, u32, R),
+  T: FnMut(&Tile, &Film),
+{
+  let (tx, rx) = mpsc::channel();
+  let next = AtomicUsize::new(0);
+  let (pixel, next) = (&pixel, &next);
+
+  thread::scope(|scope| {
+    scope.spawn(move || {
+      rayon::scope(|workers| {
+        for _ in 0..rayon::current_num_threads() {
+          let tx = tx.clone();
+          workers.spawn(move |_| loop {
+            let k = next.fetch_add(1, Ordering::Relaxed);
+            if k >= tiles.len() {
+              break;
+            }
+            let pixels = tiles[k]
+              .pixels()
+              .filter_map(|(x, y)| pixel(x, y).map(|r| (x, y, r)))
+              .collect::<Vec<_>>();
+            if tx.send((k, pixels)).is_err() {
+              break;
+            }
+          });
+        }
+      });
+    });
+
+    // the channel closes once all the workers are done
+    for (k, pixels) in rx {
+      for (x, y, r) in pixels {
+        splat(film, x, y, r);
+      }
+      on_tile(&tiles[k], film);
+    }
+  });
+}
+
+// passes are taken from the ray through the center of the pixel
+fn pixel_aovs(
+  s: &Scene,
+  conf: &RenderConfig,
+  x: u32,
+  y: u32,
+  aovs: &[Aov],
+) -> Vec<Color> {
+  let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+  let ray = s.generate_ray(cx, cy, conf.w as f32, conf.h as f32);
+  aov_sample(s, &ray, aovs)
+}
+
+// radiance of a sample at continuous pixel coordinates p, u in [0, 1)
+// picks its wavelength in spectral mode
+fn trace_sample(s: &Scene, conf: &RenderConfig, p: [f32; 2], u: f32) -> Color {
+  let (w, h) = (conf.w as f32, conf.h as f32);
+  let ray = s.generate_ray(p[0], p[1], w, h);
+  if !conf.spectral {
+    return s.trace_ray(&ray, 0).unwrap_or(Color::Green);
+  }
+
+  // single spectral samples may be out of gamut, only their average is
+  // meaningful
+  let wavelength = sample_wavelength(u);
+  let ray = ray.with_wavelength(wavelength);
+  let c = s.trace_ray(&ray, 0).unwrap_or(Color::Green);
+  spectral_to_rgb(wavelength, c.spectral_value(wavelength))
+}
+
+// random point in the k-th of n strata of [0, 1), wavelengths are
+// stratified over the samples of a pixel
+fn stratum(k: usize, n: usize) -> f32 {
+  (k as f32 + rand01()) / n as f32
+}
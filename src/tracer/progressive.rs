@@ -0,0 +1,62 @@
+// Progressive rendering, the image is refined by successive passes of one
+// sample per pixel accumulated in the film, so it can be looked at while
+// it converges. For example, up to 256 passes saved every 10 seconds:
+//
+//   .progressive(Some(
+//       Progressive::new(256).every_seconds(10.0).save_to("trace.png"),
+//   ))
+use std::time::Duration;
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub enum Snapshot {
+    // after every n passes
+    Passes(usize),
+    // after the first pass ending at least this long after the last one
+    Interval(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct Progressive {
+    // upper bound on the samples per pixel
+    pub passes: usize,
+    pub snapshot: Snapshot,
+    // where snapshots are saved, in any format the film can be saved to
+    pub path: Option<String>,
+}
+
+#[allow(unused)]
+impl Progressive {
+    // snapshots after every pass, not saved
+    pub fn new(passes: usize) -> Self {
+        Self {
+            passes,
+            snapshot: Snapshot::Passes(1),
+            path: None,
+        }
+    }
+
+    pub fn every_passes(mut self, n: usize) -> Self {
+        self.snapshot = Snapshot::Passes(n.max(1));
+        self
+    }
+
+    pub fn every_seconds(mut self, seconds: f32) -> Self {
+        self.snapshot = Snapshot::Interval(Duration::from_secs_f32(seconds));
+        self
+    }
+
+    pub fn save_to(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    // whether a snapshot is taken after `passes` passes, `elapsed` since the
+    // last snapshot
+    pub fn snapshot_due(&self, passes: usize, elapsed: Duration) -> bool {
+        match self.snapshot {
+            Snapshot::Passes(n) => passes % n.max(1) == 0,
+            Snapshot::Interval(interval) => elapsed >= interval,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// Generators of the sample positions within a pixel, in [0, 1)^2
+use crate::common::rand01;
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub enum Sampler {
+    // the same offsets for every pixel
+    Pattern(Vec<[f32; 2]>),
+    // uniformly random
+    Random(usize),
+    // one jittered sample in each cell of an n x n grid
+    Stratified(usize),
+    // Halton sequence in bases 2 and 3
+    Halton(usize),
+    // first two dimensions of the Sobol sequence
+    Sobol(usize),
+    // blue noise pattern made by best-candidate sampling
+    BlueNoise(Vec<[f32; 2]>),
+}
+
+#[allow(unused)]
+impl Sampler {
+    // pattern of `count` points, each one chosen among candidates as the
+    // farthest from the points before it
+    // See: https://www.cs.virginia.edu/~gfx/courses/2007/ImageSynthesis/assignments/mitchell.pdf
+    pub fn blue_noise(count: usize) -> Self {
+        let mut points: Vec<[f32; 2]> = Vec::with_capacity(count);
+        for k in 0..count {
+            let candidates = (k + 1) * 8;
+            let best = (0..candidates)
+                .map(|_| [rand01(), rand01()])
+                .map(|p| (min_toroidal_dist2(&points, p), p))
+                .fold((-1.0, [0.0; 2]), |a, b| if b.0 > a.0 { b } else { a });
+            points.push(best.1);
+        }
+        Sampler::BlueNoise(points)
+    }
+
+    pub fn count(&self) -> usize {
+        match self {
+            Sampler::Pattern(offsets) => offsets.len(),
+            Sampler::Random(n) | Sampler::Halton(n) | Sampler::Sobol(n) => *n,
+            Sampler::Stratified(n) => n * n,
+            Sampler::BlueNoise(points) => points.len(),
+        }
+    }
+
+    // k-th sample of the pixel at (x, y). Low discrepancy sequences are
+    // shifted per pixel, so neighbouring pixels don't share a pattern.
+    // Adaptive and progressive sampling may ask for more than `count`
+    // samples, fixed patterns then go on with the Sobol sequence rather
+    // than repeating their points.
+    pub fn sample(&self, x: u32, y: u32, k: usize) -> [f32; 2] {
+        let seed = hash(x, y);
+        match self {
+            Sampler::Pattern(offsets) if k < offsets.len() => offsets[k],
+            Sampler::Random(_) => [rand01(), rand01()],
+            Sampler::Stratified(n) => {
+                let n = (*n).max(1);
+                let cell = k % (n * n);
+                let (i, j) = (cell % n, cell / n);
+                [
+                    (i as f32 + rand01()) / n as f32,
+                    (j as f32 + rand01()) / n as f32,
+                ]
+            }
+            Sampler::Halton(_) => {
+                let k = k as u32;
+                shift([radical_inverse(k, 2), radical_inverse(k, 3)], seed)
+            }
+            Sampler::BlueNoise(points) if k < points.len() => {
+                shift(points[k], seed)
+            }
+            Sampler::Sobol(_) | Sampler::Pattern(_) | Sampler::BlueNoise(_) => {
+                sobol(k as u32, seed)
+            }
+        }
+    }
+
+    pub fn samples(&self, x: u32, y: u32) -> Vec<[f32; 2]> {
+        (0..self.count()).map(|k| self.sample(x, y, k)).collect()
+    }
+}
+
+// k-th point of the van der Corput sequence shifted per pixel, the first
+// points spread evenly over [0, 1) however many are taken
+pub fn progressive_1d(x: u32, y: u32, k: usize) -> f32 {
+    let shift = to_unit(hash(hash(x, y), 0x85eb_ca6b));
+    let v = radical_inverse(k as u32, 2) + shift;
+    v - v.floor()
+}
+
+fn min_toroidal_dist2(points: &[[f32; 2]], p: [f32; 2]) -> f32 {
+    let d = |a: f32, b: f32| {
+        let d = (a - b).abs();
+        d.min(1.0 - d)
+    };
+    points
+        .iter()
+        .map(|q| d(p[0], q[0]).powi(2) + d(p[1], q[1]).powi(2))
+        .fold(std::f32::INFINITY, f32::min)
+}
+
+fn radical_inverse(mut i: u32, base: u32) -> f32 {
+    let inv = 1.0 / base as f32;
+    let (mut f, mut r) = (inv, 0.0);
+    while i > 0 {
+        r += (i % base) as f32 * f;
+        i /= base;
+        f *= inv;
+    }
+    r
+}
+
+// first two dimensions of the Sobol sequence, random digit scrambling
+// keeps the stratification
+fn sobol(k: u32, seed: u32) -> [f32; 2] {
+    let (s0, s1) = (seed, hash(seed, 0x9e37_79b9));
+    let u = van_der_corput(k) ^ s0;
+    let v = sobol2(k) ^ s1;
+    [to_unit(u), to_unit(v)]
+}
+
+fn van_der_corput(i: u32) -> u32 {
+    i.reverse_bits()
+}
+
+// second dimension of the Sobol sequence
+fn sobol2(mut i: u32) -> u32 {
+    let mut v = 1 << 31;
+    let mut r = 0;
+    while i != 0 {
+        if i & 1 != 0 {
+            r ^= v;
+        }
+        i >>= 1;
+        v ^= v >> 1;
+    }
+    r
+}
+
+fn to_unit(u: u32) -> f32 {
+    // keeps the result below 1.0
+    (u >> 8) as f32 / (1u32 << 24) as f32
+}
+
+// toroidal shift by a random offset derived from the seed
+fn shift(p: [f32; 2], seed: u32) -> [f32; 2] {
+    let dx = to_unit(seed);
+    let dy = to_unit(hash(seed, 1));
+    let wrap = |v: f32| v - v.floor();
+    [wrap(p[0] + dx), wrap(p[1] + dy)]
+}
+
+// integer hash mixing two values
+fn hash(x: u32, y: u32) -> u32 {
+    let mut h = x.wrapping_mul(0x8da6_b343) ^ y.wrapping_mul(0xd816_3841);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^ (h >> 16)
+}
@@ -0,0 +1,147 @@
+// Tiles (buckets) the image is rendered by. Threads take them in order and
+// each one is delivered once done, while the render goes on. For example,
+// 64 pixel tiles along a Hilbert curve over a crop of the image:
+//
+//   .tiles(Tiles::new(64, TileOrder::Hilbert))
+//   .region(Some(Tile::new(256, 256, 512, 512)))
+use std::f32::consts::PI;
+
+// rectangle of pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[allow(unused)]
+impl Tile {
+    pub fn new(x: u32, y: u32, w: u32, h: u32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    // pixels row by row
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32)> {
+        let (x0, x1) = (self.x, self.x + self.w);
+        (self.y..self.y + self.h)
+            .flat_map(move |y| (x0..x1).map(move |x| (x, y)))
+    }
+
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        (self.x..self.x + self.w).contains(&x)
+            && (self.y..self.y + self.h).contains(&y)
+    }
+
+    pub fn intersect(&self, other: &Tile) -> Option<Tile> {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x + self.w).min(other.x + other.w);
+        let y1 = (self.y + self.h).min(other.y + other.h);
+        if x0 >= x1 || y0 >= y1 {
+            return None;
+        }
+        Some(Tile::new(x0, y0, x1 - x0, y1 - y0))
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileOrder {
+    // row by row from the top
+    Scanline,
+    // outwards from the center, where the subject usually is
+    Spiral,
+    // along a Hilbert curve padded to a square power of 2 grid, consecutive
+    // tiles are adjacent on such grids but may jump where the padding is
+    // dropped
+    Hilbert,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tiles {
+    // width and height of the tiles in pixels
+    pub size: u32,
+    pub order: TileOrder,
+}
+
+impl Default for Tiles {
+    fn default() -> Self {
+        Self {
+            size: 32,
+            order: TileOrder::Spiral,
+        }
+    }
+}
+
+#[allow(unused)]
+impl Tiles {
+    pub fn new(size: u32, order: TileOrder) -> Self {
+        Self { size, order }
+    }
+
+    // tiles covering the region, in the order they are rendered. Tiles on
+    // the right and bottom edges may be smaller.
+    pub fn split(&self, region: &Tile) -> Vec<Tile> {
+        let size = self.size.max(1);
+        let nx = (region.w + size - 1) / size;
+        let ny = (region.h + size - 1) / size;
+        let mut cells = (0..ny)
+            .flat_map(|j| (0..nx).map(move |i| (i, j)))
+            .collect::<Vec<_>>();
+
+        match self.order {
+            TileOrder::Scanline => {}
+            TileOrder::Spiral => {
+                let cx = (nx as f32 - 1.0) / 2.0;
+                let cy = (ny as f32 - 1.0) / 2.0;
+                // by ring around the center, then clockwise from the top
+                let key = |&(i, j): &(u32, u32)| {
+                    let (dx, dy) = (i as f32 - cx, j as f32 - cy);
+                    let ring = dx.abs().max(dy.abs()).round() as u32;
+                    let angle = dx.atan2(-dy);
+                    let angle = (angle + 2.0 * PI) % (2.0 * PI);
+                    (ring, (angle * 1e4) as u32)
+                };
+                cells.sort_by_key(key);
+            }
+            TileOrder::Hilbert => {
+                let n = nx.max(ny).next_power_of_two();
+                cells.sort_by_key(|&(i, j)| hilbert_index(n, i, j));
+            }
+        }
+
+        cells
+            .into_iter()
+            .map(|(i, j)| {
+                let (x, y) = (region.x + i * size, region.y + j * size);
+                let w = size.min(region.x + region.w - x);
+                let h = size.min(region.y + region.h - y);
+                Tile::new(x, y, w, h)
+            })
+            .collect()
+    }
+}
+
+// distance of the cell (x, y) along the Hilbert curve filling an n x n
+// grid, n a power of 2
+// See: https://en.wikipedia.org/wiki/Hilbert_curve
+fn hilbert_index(n: u32, mut x: u32, mut y: u32) -> u64 {
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u32;
+        let ry = (y & s > 0) as u32;
+        d += s as u64 * s as u64 * ((3 * rx) ^ ry) as u64;
+        // rotate the quadrant
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - (x & (s - 1));
+                y = s - 1 - (y & (s - 1));
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}